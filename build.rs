@@ -6,7 +6,7 @@ use std::path::Path;
 use std::io::Write;
 use std::fs::File;
 
-use rusoto_codegen::{Service, generate};
+use rusoto_codegen::{Service, generate, generate_endpoints};
 
 /// Parses and generates variables used to construct a User-Agent.
 ///
@@ -88,6 +88,7 @@ fn main() {
         generate(service, out_path);
     }
 
+    generate_endpoints(out_path);
     generate_user_agent_vars(out_path);
 
     let codegen_dir = Path::new("codegen");
@@ -0,0 +1,160 @@
+//! Generates the tables of the endpoint resolver from botocore's `endpoints.json`.
+//!
+//! The services and endpoints of every partition are written out sorted by name, so the
+//! resolver can binary search them.
+
+use serde_json::Value;
+
+pub fn generate_source(endpoints: &Value) -> String {
+    let partitions = endpoints.find("partitions")
+        .and_then(Value::as_array)
+        .expect("endpoints.json has no partitions");
+
+    format!("// Generated from botocore's endpoints.json, do not edit.
+static PARTITIONS: &'static [Partition] = &[
+{partitions}];
+",
+        partitions = partitions.iter().map(generate_partition).collect::<Vec<String>>().join(""))
+}
+
+fn generate_partition(partition: &Value) -> String {
+    let services = partition.find("services")
+        .and_then(Value::as_object)
+        .map(|services| services.iter().map(|(name, service)| generate_service(name, service)).collect::<Vec<String>>())
+        .unwrap_or_else(Vec::new);
+
+    format!("    Partition {{
+        name: {name},
+        dns_suffix: {dns_suffix},
+        region_regex: {region_regex},
+        regions: &[{regions}],
+        defaults: {defaults},
+        services: &[
+{services}        ],
+    }},
+",
+        name = required_string(partition, "partition"),
+        dns_suffix = required_string(partition, "dnsSuffix"),
+        region_regex = required_string(partition, "regionRegex"),
+        regions = keys(partition.find("regions")).join(", "),
+        defaults = generate_endpoint_data(partition.find("defaults")),
+        services = services.join(""))
+}
+
+fn generate_service(name: &str, service: &Value) -> String {
+    let endpoints = service.find("endpoints")
+        .and_then(Value::as_object)
+        .map(|endpoints| {
+            endpoints.iter()
+                .map(|(name, endpoint)| format!("                ({:?}, {}),\n", name, generate_endpoint_data(Some(endpoint))))
+                .collect::<Vec<String>>()
+        })
+        .unwrap_or_else(Vec::new);
+
+    format!("            ServiceEndpoints {{
+                name: {name:?},
+                partition_endpoint: {partition_endpoint},
+                is_regionalized: {is_regionalized},
+                defaults: {defaults},
+                endpoints: &[
+{endpoints}                ],
+            }},
+",
+        name = name,
+        partition_endpoint = optional_string(Some(service), "partitionEndpoint"),
+        is_regionalized = service.find("isRegionalized").and_then(Value::as_bool).unwrap_or(true),
+        defaults = generate_endpoint_data(service.find("defaults")),
+        endpoints = endpoints.join(""))
+}
+
+fn generate_endpoint_data(data: Option<&Value>) -> String {
+    let credential_scope = data.and_then(|data| data.find("credentialScope"));
+
+    format!("EndpointData {{ hostname: {hostname}, protocols: {protocols}, signature_versions: {signature_versions}, \
+             credential_scope_region: {credential_scope_region}, credential_scope_service: {credential_scope_service} }}",
+        hostname = optional_string(data, "hostname"),
+        protocols = optional_strings(data, "protocols"),
+        signature_versions = optional_strings(data, "signatureVersions"),
+        credential_scope_region = optional_string(credential_scope, "region"),
+        credential_scope_service = optional_string(credential_scope, "service"))
+}
+
+fn required_string(value: &Value, key: &str) -> String {
+    match value.find(key).and_then(Value::as_str) {
+        Some(string) => format!("{:?}", string),
+        None => panic!("endpoints.json is missing {} in {:?}", key, value),
+    }
+}
+
+fn optional_string(value: Option<&Value>, key: &str) -> String {
+    match value.and_then(|value| value.find(key)).and_then(Value::as_str) {
+        Some(string) => format!("Some({:?})", string),
+        None => "None".to_owned(),
+    }
+}
+
+fn optional_strings(value: Option<&Value>, key: &str) -> String {
+    match value.and_then(|value| value.find(key)).and_then(Value::as_array) {
+        Some(strings) => {
+            let literals: Vec<String> = strings.iter()
+                .filter_map(Value::as_str)
+                .map(|string| format!("{:?}", string))
+                .collect();
+            format!("Some(&[{}])", literals.join(", "))
+        }
+        None => "None".to_owned(),
+    }
+}
+
+fn keys(value: Option<&Value>) -> Vec<String> {
+    value.and_then(Value::as_object)
+        .map(|object| object.keys().map(|key| format!("{:?}", key)).collect())
+        .unwrap_or_else(Vec::new)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{self, Value};
+
+    use super::generate_source;
+
+    #[test]
+    fn generate_partition_tables() {
+        let endpoints: Value = serde_json::from_str(r#"{
+            "partitions": [{
+                "partition": "aws",
+                "dnsSuffix": "amazonaws.com",
+                "regionRegex": "^(us|eu)\\-\\w+\\-\\d+$",
+                "defaults": {"hostname": "{service}.{region}.{dnsSuffix}", "protocols": ["https"], "signatureVersions": ["v4"]},
+                "regions": {"us-east-1": {"description": "US East (N. Virginia)"}, "eu-west-1": {}},
+                "services": {
+                    "iam": {
+                        "partitionEndpoint": "aws-global",
+                        "isRegionalized": false,
+                        "endpoints": {
+                            "aws-global": {"hostname": "iam.amazonaws.com", "credentialScope": {"region": "us-east-1"}}
+                        }
+                    },
+                    "dynamodb": {"defaults": {"protocols": ["http", "https"]}, "endpoints": {"us-east-1": {}}}
+                }
+            }]
+        }"#).unwrap();
+
+        let source = generate_source(&endpoints);
+
+        assert!(source.contains("name: \"aws\","));
+        assert!(source.contains("region_regex: \"^(us|eu)\\\\-\\\\w+\\\\-\\\\d+$\","));
+        assert!(source.contains("regions: &[\"eu-west-1\", \"us-east-1\"],"));
+        assert!(source.contains("defaults: EndpointData { hostname: Some(\"{service}.{region}.{dnsSuffix}\"), \
+                                 protocols: Some(&[\"https\"]), signature_versions: Some(&[\"v4\"]), \
+                                 credential_scope_region: None, credential_scope_service: None },"));
+        assert!(source.contains("partition_endpoint: Some(\"aws-global\"),\n                is_regionalized: false,"));
+        assert!(source.contains("(\"aws-global\", EndpointData { hostname: Some(\"iam.amazonaws.com\"), protocols: None, \
+                                 signature_versions: None, credential_scope_region: Some(\"us-east-1\"), \
+                                 credential_scope_service: None }),"));
+        assert!(source.contains("partition_endpoint: None,\n                is_regionalized: true,"));
+
+        // services are sorted, so they can be binary searched
+        assert!(source.find("name: \"dynamodb\"").unwrap() < source.find("name: \"iam\"").unwrap());
+    }
+}
@@ -32,6 +32,33 @@ pub trait GenerateErrorTypes {
 
             {error_from_body_impl}
             {error_from_type_impl}
+            impl {type_name} {{
+                /// The metadata of the response the error was read from, if there was one.
+                pub fn response_metadata(&self) -> Option<&ResponseMetadata> {{
+                    match *self {{
+                        {type_name}::HttpDispatch(_) | {type_name}::Credentials(_) | {type_name}::ParseError(_) => None,
+                        {metadata_matchers}
+                    }}
+                }}
+            }}
+            impl AwsError for {type_name} {{
+                fn code(&self) -> Option<&str> {{
+                    match *self {{
+                        {code_matchers}
+                    }}
+                }}
+
+                fn status(&self) -> Option<u16> {{
+                    self.response_metadata().map(|metadata| metadata.status)
+                }}
+
+                fn http_dispatch_error(&self) -> Option<&HttpDispatchError> {{
+                    match *self {{
+                        {type_name}::HttpDispatch(ref dispatch_error) => Some(dispatch_error),
+                        _ => None,
+                    }}
+                }}
+            }}
             impl From<CredentialsError> for {type_name} {{
                 fn from(err: CredentialsError) -> {type_name} {{
                     {type_name}::Credentials(err)
@@ -44,7 +71,10 @@ pub trait GenerateErrorTypes {
             }}
             impl fmt::Display for {type_name} {{
                 fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {{
-                    write!(f, \"{{}}\", self.description())
+                    match *self {{
+                        {type_name}::HttpDispatch(ref dispatch_error) => write!(f, \"{{}}\", dispatch_error),
+                        _ => write!(f, \"{{}}\", self.description()),
+                    }}
                 }}
             }}
             impl Error for {type_name} {{
@@ -53,11 +83,21 @@ pub trait GenerateErrorTypes {
                      {description_matchers}
                  }}
              }}
+
+                fn cause(&self) -> Option<&Error> {{
+                    match *self {{
+                        {type_name}::HttpDispatch(ref dispatch_error) => Some(dispatch_error),
+                        {type_name}::Credentials(ref err) => Some(err),
+                        _ => None,
+                    }}
+                }}
          }}
          ",
          type_name = operation.error_type_name(),
          error_from_body_impl = self.generate_error_from_body_impl(operation),
          error_from_type_impl = self.generate_error_from_type_impl(operation),
+         metadata_matchers = self.generate_error_metadata_matchers(operation),
+         code_matchers = self.generate_error_code_matchers(operation),
          error_types = self.generate_error_enum_types(operation, error_documentation).unwrap_or(String::from("")),
          description_matchers = self.generate_error_description_matchers(operation).unwrap_or(String::from("")))
     }
@@ -72,7 +112,7 @@ pub trait GenerateErrorTypes {
                 // some botocore definitions include Validation in every errors list, some take it as assumed
                 // skip it if it's listed, as we implement it for all error types below
                 if error.idiomatic_error_name() != "Validation" {
-                    enum_types.push(format!("\n///{}\n{}(String)",
+                    enum_types.push(format!("\n///{}\n{}(String, ResponseMetadata)",
                         error_documentation.get(&error.shape).unwrap_or(&&String::from("")),
                         error.idiomatic_error_name()));
                 }
@@ -81,8 +121,9 @@ pub trait GenerateErrorTypes {
 
         enum_types.push("/// An error occurred dispatching the HTTP request\nHttpDispatch(HttpDispatchError)".to_string());
         enum_types.push("/// An error was encountered with AWS credentials.\nCredentials(CredentialsError)".to_string());
-        enum_types.push("/// A validation error occurred.  Details from AWS are provided.\nValidation(String)".to_string());
-        enum_types.push("/// An unknown error occurred.  The raw HTTP response is provided.\nUnknown(String)".to_string());
+        enum_types.push("/// A validation error occurred.  Details from AWS are provided.\nValidation(String, ResponseMetadata)".to_string());
+        enum_types.push("/// An error response that didn't match any of the modeled errors.  Its code, message and body are provided.\nUnknown(UnknownError)".to_string());
+        enum_types.push("/// A successful response that couldn't be deserialized, which is a bug in the client rather than an error from AWS.\nParseError(String)".to_string());
         Some(enum_types.join(","))
     }
 
@@ -97,19 +138,62 @@ pub trait GenerateErrorTypes {
                 // some botocore definitions include Validation in every errors list, some take it as assumed
                 // skip it if it's listed, as we implement it for all error types below
                 if error.idiomatic_error_name() != "Validation" {
-                    type_matchers.push(format!("{error_type}::{error_shape}(ref cause) => cause",
+                    type_matchers.push(format!("{error_type}::{error_shape}(ref cause, _) => cause",
                         error_type = operation.error_type_name(),
                         error_shape = error.idiomatic_error_name()))
                 }
             }
         }
 
-        type_matchers.push(format!("{error_type}::Validation(ref cause) => cause", error_type = error_type));
+        type_matchers.push(format!("{error_type}::Validation(ref cause, _) => cause", error_type = error_type));
         type_matchers.push(format!("{error_type}::Credentials(ref err) => err.description()", error_type = error_type));
         type_matchers.push(format!("{error_type}::HttpDispatch(ref dispatch_error) => dispatch_error.description()", error_type = error_type));
-        type_matchers.push(format!("{error_type}::Unknown(ref cause) => cause", error_type = error_type));
+        type_matchers.push(format!("{error_type}::Unknown(ref unknown) => &unknown.message", error_type = error_type));
+        type_matchers.push(format!("{error_type}::ParseError(ref cause) => cause", error_type = error_type));
         Some(type_matchers.join(","))
     }
+
+    /// generate the matcher arms for an error type's response_metadata(), one for each error read from a response
+    fn generate_error_metadata_matchers(&self, operation: &Operation) -> String {
+        let mut type_matchers: Vec<String> = Vec::new();
+        let error_type = operation.error_type_name();
+
+        if operation.errors.is_some() {
+            for error in operation.errors.as_ref().unwrap().iter() {
+                if error.idiomatic_error_name() != "Validation" {
+                    type_matchers.push(format!("{error_type}::{error_shape}(_, ref metadata) => Some(metadata)",
+                        error_type = error_type,
+                        error_shape = error.idiomatic_error_name()))
+                }
+            }
+        }
+
+        type_matchers.push(format!("{error_type}::Validation(_, ref metadata) => Some(metadata)", error_type = error_type));
+        type_matchers.push(format!("{error_type}::Unknown(ref unknown) => Some(&unknown.metadata)", error_type = error_type));
+        type_matchers.join(",")
+    }
+
+    /// generate the matcher arms for an error type's implementation of AwsError.code()
+    fn generate_error_code_matchers(&self, operation: &Operation) -> String {
+        let mut type_matchers: Vec<String> = Vec::new();
+        let error_type = operation.error_type_name();
+
+        if operation.errors.is_some() {
+            for error in operation.errors.as_ref().unwrap().iter() {
+                if error.idiomatic_error_name() != "Validation" {
+                    type_matchers.push(format!("{error_type}::{error_name}(..) => Some(\"{error_shape}\")",
+                        error_type = error_type,
+                        error_name = error.idiomatic_error_name(),
+                        error_shape = error.shape))
+                }
+            }
+        }
+
+        type_matchers.push(format!("{error_type}::Validation(..) => Some(\"ValidationException\")", error_type = error_type));
+        type_matchers.push(format!("{error_type}::Unknown(ref unknown) => unknown.code.as_ref().map(|code| &code[..])", error_type = error_type));
+        type_matchers.push(format!("{error_type}::HttpDispatch(_) | {error_type}::Credentials(_) | {error_type}::ParseError(_) => None", error_type = error_type));
+        type_matchers.join(",")
+    }
 }
 
 pub struct JsonErrorTypes;
@@ -120,7 +204,7 @@ impl GenerateErrorTypes for XmlErrorTypes {
     fn generate_error_from_body_impl(&self, operation: &Operation) -> String {
         format!("
             impl {type_name} {{
-                pub fn from_body(body: &str) -> {type_name} {{
+                pub fn from_body(body: &str, metadata: ResponseMetadata) -> {type_name} {{
                     let mut reader = EventReader::new(body.as_bytes());
                     let mut stack = XmlResponse::new(reader.events().peekable());
                     let _start_document = stack.next();
@@ -131,7 +215,7 @@ impl GenerateErrorTypes for XmlErrorTypes {
                                 {type_matchers}
                             }}
                        }},
-                       Err(_) => {type_name}::Unknown(body.to_string())
+                       Err(_) => {type_name}::Unknown(UnknownError::new(None, body, body, metadata))
                    }}
                 }}
             }}",
@@ -144,7 +228,7 @@ impl GenerateErrorTypes for XmlErrorTypes {
             impl From<XmlParseError> for {type_name} {{
                 fn from(err: XmlParseError) -> {type_name} {{
                     let XmlParseError(message) = err;
-                    {type_name}::Unknown(message.to_string())
+                    {type_name}::ParseError(message.to_string())
                 }}
             }}",
             type_name = operation.error_type_name())
@@ -160,14 +244,14 @@ impl XmlErrorTypes {
 
         if operation.errors.is_some() {
             for error in operation.errors.as_ref().unwrap().iter() {
-                type_matchers.push(format!("\"{error_shape}\" => {error_type}::{error_name}(String::from(parsed_error.message))",
+                type_matchers.push(format!("\"{error_shape}\" => {error_type}::{error_name}(String::from(parsed_error.message), metadata)",
                     error_shape = error.shape,
                     error_type = error_type,
                     error_name = error.idiomatic_error_name()))
             }
         }
 
-        type_matchers.push(format!("_ => {error_type}::Unknown(String::from(body))",  error_type = error_type));
+        type_matchers.push(format!("_ => {error_type}::Unknown(UnknownError::new(Some(&parsed_error.code[..]), parsed_error.message, body, metadata))",  error_type = error_type));
         type_matchers.join(",")
     }
 
@@ -177,17 +261,19 @@ impl GenerateErrorTypes for JsonErrorTypes {
     fn generate_error_from_body_impl(&self, operation: &Operation) -> String {
         format!("
             impl {type_name} {{
-                pub fn from_body(body: &str) -> {type_name} {{
+                pub fn from_body(body: &str, metadata: ResponseMetadata) -> {type_name} {{
                     match from_str::<SerdeJsonValue>(body) {{
                         Ok(json) => {{
-                            let error_type = json.find(\"__type\").and_then(|e| e.as_str()).unwrap_or(\"Unknown\");
-                            let error_message = json.find(\"message\").and_then(|m| m.as_str()).unwrap_or(body);
+                            // some services prefix the type with a namespace, as in
+                            // \"com.amazonaws.dynamodb.v20120810#ResourceNotFoundException\"
+                            let error_type = json.find(\"__type\").and_then(|e| e.as_str()).map(|e| e.rsplit('#').next().unwrap_or(e));
+                            let error_message = json.find(\"message\").or_else(|| json.find(\"Message\")).and_then(|m| m.as_str()).unwrap_or(body);
 
-                            match error_type {{
+                            match error_type.unwrap_or(\"\") {{
                                 {type_matchers}
                             }}
                         }},
-                        Err(_) => {type_name}::Unknown(String::from(body))
+                        Err(_) => {type_name}::Unknown(UnknownError::new(None, body, body, metadata))
                     }}
                 }}
             }}",
@@ -196,14 +282,14 @@ impl GenerateErrorTypes for JsonErrorTypes {
     }
 
     fn generate_error_from_type_impl(&self, operation: &Operation) -> String {
-        format!("            
+        format!("
             impl From<serde_json::error::Error> for {type_name} {{
                 fn from(err: serde_json::error::Error) -> {type_name} {{
-                    {type_name}::Unknown(err.description().to_string())
+                    {type_name}::ParseError(err.description().to_string())
                 }}
             }}",
             type_name = operation.error_type_name())
-    } 
+    }
 }
 
 impl JsonErrorTypes {
@@ -216,15 +302,15 @@ impl JsonErrorTypes {
         if operation.errors.is_some() {
             for error in operation.errors.as_ref().unwrap().iter() {
                 if error.shape != "ValidationException" {
-                    type_matchers.push(format!("\"{error_shape}\" => {error_type}::{error_name}(String::from(error_message))",
+                    type_matchers.push(format!("\"{error_shape}\" => {error_type}::{error_name}(String::from(error_message), metadata)",
                         error_shape = error.shape,
                         error_type = error_type,
                         error_name = error.idiomatic_error_name()))
                 }
             }
         }
-        type_matchers.push(format!("\"ValidationException\" => {error_type}::Validation(error_message.to_string())", error_type = error_type));
-        type_matchers.push(format!("_ => {error_type}::Unknown(String::from(body))",  error_type = error_type));
+        type_matchers.push(format!("\"ValidationException\" => {error_type}::Validation(error_message.to_string(), metadata)", error_type = error_type));
+        type_matchers.push(format!("_ => {error_type}::Unknown(UnknownError::new(error_type, error_message, body, metadata))",  error_type = error_type));
         type_matchers.join(",")
     }
 
@@ -15,34 +15,50 @@ impl GenerateProtocol for JsonGenerator {
             format!("
                 {documentation}
                 {method_signature} -> Result<{output_type}, {error_type}> {{
-                    {payload}
-                    let mut request = SignedRequest::new(\"{http_method}\", \"{signing_name}\", self.region, \"{request_uri}\");
-                    {modify_endpoint_prefix}
-                    request.set_content_type(\"application/x-amz-json-{json_version}\".to_owned());
-                    request.add_header(\"x-amz-target\", \"{target_prefix}.{name}\");
-                    request.set_payload(payload);
-                    request.sign(&try!(self.credentials_provider.credentials()));
-                    let response = try!(self.dispatcher.dispatch(&request));
-
-                    match response.status {{
-                        200 => {{
-                            {ok_response}
-                        }}
-                        _ => Err({error_type}::from_body(&response.body)),
-                    }}
+                    self.{method_name}_with_metadata({arguments}).map(|(output, _)| output)
+                }}
+
+                /// Like `{method_name}`, but also returns the metadata of the response.
+                {method_with_metadata_signature} -> Result<({output_type}, ResponseMetadata), {error_type}> {{
+                    {request}
+                    let response = try!(self.retry_policy.sign_and_dispatch(&self.dispatcher, &self.interceptors, &mut request, &try!(self.credentials_provider.credentials())));
+
+                    {response_handler}
                 }}
                 ",
                 documentation = generate_documentation(operation).unwrap_or("".to_owned()),
-                method_signature = generate_method_signature(operation),
-                payload = generate_payload(operation),
-                signing_name = service.signing_name(),
-                modify_endpoint_prefix = generate_endpoint_modification(service).unwrap_or("".to_owned()),
-                http_method = operation.http.method,
-                name = operation.name,
-                ok_response = generate_ok_response(operation, output_type),
-                request_uri = operation.http.request_uri,
-                target_prefix = service.metadata.target_prefix.as_ref().unwrap(),
-                json_version = service.metadata.json_version.as_ref().unwrap(),
+                method_name = operation.name.to_snake_case(),
+                method_signature = generate_method_signature(&operation.name.to_snake_case(), operation),
+                method_with_metadata_signature = generate_method_signature(&format!("{}_with_metadata", operation.name.to_snake_case()), operation),
+                arguments = generate_method_arguments(operation),
+                request = generate_request(service, operation),
+                response_handler = generate_response_handler(operation, output_type),
+                error_type = operation.error_type_name(),
+                output_type = output_type
+            )
+        }).collect::<Vec<String>>().join("\n")
+    }
+
+    fn generate_async_methods(&self, service: &Service) -> String {
+        service.operations.values().map(|operation| {
+
+            let output_type = operation.output_shape_or("()");
+
+            format!("
+                /// Like `{method_name}`, but returns a future instead of blocking.
+                {method_signature} -> RusotoFuture<{output_type}, {error_type}> {{
+                    {request}
+                    Box::new(sign_and_dispatch_async(&self.credentials_provider, &self.dispatcher, &self.retry_policy, &self.interceptors, &mut request)
+                        .and_then(|response| -> Result<({output_type}, ResponseMetadata), {error_type}> {{
+                            {response_handler}
+                        }})
+                        .map(|(output, _)| output))
+                }}
+                ",
+                method_name = operation.name.to_snake_case(),
+                method_signature = generate_method_signature(&format!("{}_async", operation.name.to_snake_case()), operation),
+                request = generate_request(service, operation),
+                response_handler = generate_response_handler(operation, output_type),
                 error_type = operation.error_type_name(),
                 output_type = output_type
             )
@@ -122,21 +138,68 @@ fn generate_endpoint_modification(service: &Service) -> Option<String> {
     }
 }
 
-fn generate_method_signature(operation: &Operation) -> String {
+fn generate_method_signature(method_name: &str, operation: &Operation) -> String {
     if operation.input.is_some() {
         format!(
             "pub fn {method_name}(&self, input: &{input_type}) ",
             input_type = operation.input_shape(),
-            method_name = operation.name.to_snake_case()
+            method_name = method_name
         )
     } else {
         format!(
             "pub fn {method_name}(&self) ",
-            method_name = operation.name.to_snake_case()
+            method_name = method_name
         )
     }
 }
 
+fn generate_method_arguments(operation: &Operation) -> &'static str {
+    if operation.input.is_some() {
+        "input"
+    } else {
+        ""
+    }
+}
+
+/// Builds `request`, ready to be signed and dispatched.
+fn generate_request(service: &Service, operation: &Operation) -> String {
+    format!("
+        {payload}
+        let mut request = SignedRequest::new(\"{http_method}\", \"{signing_name}\", self.region.clone(), \"{request_uri}\");
+        request.set_endpoint(self.endpoint.clone());
+        {modify_endpoint_prefix}
+        request.set_content_type(\"application/x-amz-json-{json_version}\".to_owned());
+        request.add_header(\"x-amz-target\", \"{target_prefix}.{name}\");
+        request.set_payload(payload);
+        ",
+        payload = generate_payload(operation),
+        signing_name = service.signing_name(),
+        modify_endpoint_prefix = generate_endpoint_modification(service).unwrap_or("".to_owned()),
+        http_method = operation.http.method,
+        name = operation.name,
+        request_uri = operation.http.request_uri,
+        target_prefix = service.metadata.target_prefix.as_ref().unwrap(),
+        json_version = service.metadata.json_version.as_ref().unwrap(),
+    )
+}
+
+/// Turns `response` into the operation's result, along with the response's metadata.
+fn generate_response_handler(operation: &Operation, output_type: &str) -> String {
+    format!("
+        let metadata = ResponseMetadata::from_response(&response);
+
+        match response.status {{
+            200 => {{
+                {ok_response}
+            }}
+            _ => Err({error_type}::from_body(&String::from_utf8_lossy(&response.body), metadata)),
+        }}
+        ",
+        ok_response = generate_ok_response(operation, output_type),
+        error_type = operation.error_type_name(),
+    )
+}
+
 fn generate_payload(operation: &Operation) -> String {
     if operation.input.is_some() {
         "let encoded = serde_json::to_string(input).unwrap();
@@ -154,9 +217,9 @@ fn generate_documentation(operation: &Operation) -> Option<String> {
 
 fn generate_ok_response(operation: &Operation, output_type: &str) -> String {
     if operation.output.is_some() {
-        format!("Ok(serde_json::from_str::<{}>(&response.body).unwrap())", output_type)
+        format!("Ok((try!(serde_json::from_slice::<{}>(&response.body)), metadata))", output_type)
     } else {
-        "Ok(())".to_owned()
+        "Ok(((), metadata))".to_owned()
     }
 }
 
@@ -17,6 +17,8 @@ mod tests;
 pub trait GenerateProtocol {
     fn generate_methods(&self, service: &Service) -> String;
 
+    fn generate_async_methods(&self, service: &Service) -> String;
+
     fn generate_prelude(&self, service: &Service) -> String;
 
     fn generate_struct_attributes(&self, struct_name: &str) -> String;
@@ -51,12 +53,21 @@ fn generate<P, E>(service: &Service, protocol_generator: P, error_type_generator
     format!(
         "
         use hyper::Client;
-        use hyper::client::RedirectPolicy;
-        use request::DispatchSignedRequest;
+        #[cfg(feature = \"async\")]
+        use futures::Future;
+        use request::{{DispatchSignedRequest, default_client}};
+        #[cfg(feature = \"async\")]
+        use future::{{DispatchSignedRequestAsync, RusotoFuture, sign_and_dispatch_async}};
+        use endpoint::Endpoint;
+        use error::{{AwsError, UnknownError}};
+        use interceptor::{{Interceptor, InterceptorChain}};
+        use metadata::ResponseMetadata;
         use region;
+        use retry::RetryPolicy;
 
         use std::fmt;
         use std::error::Error;
+        use std::sync::Arc;
         use credential::{{CredentialsError, ProvideAwsCredentials}};
         use request::HttpDispatchError;
 
@@ -83,14 +94,15 @@ where P: GenerateProtocol {
         pub struct {type_name}<P, D> where P: ProvideAwsCredentials, D: DispatchSignedRequest {{
             credentials_provider: P,
             region: region::Region,
+            endpoint: Option<Endpoint>,
             dispatcher: D,
+            retry_policy: Arc<RetryPolicy>,
+            interceptors: InterceptorChain,
         }}
 
         impl<P> {type_name}<P, Client> where P: ProvideAwsCredentials {{
             pub fn new(credentials_provider: P, region: region::Region) -> Self {{
-                let mut client = Client::new();                
-                client.set_redirect_policy(RedirectPolicy::FollowNone);
-               {type_name}::with_request_dispatcher(client, credentials_provider, region)
+                {type_name}::with_request_dispatcher(default_client(), credentials_provider, region)
             }}
         }}
 
@@ -99,13 +111,37 @@ where P: GenerateProtocol {
                   {type_name} {{
                     credentials_provider: credentials_provider,
                     region: region,
-                    dispatcher: request_dispatcher
+                    endpoint: None,
+                    dispatcher: request_dispatcher,
+                    retry_policy: Arc::new(RetryPolicy::default()),
+                    interceptors: InterceptorChain::new(),
                 }}
             }}
+
+            /// Send requests to `endpoint` instead of the default endpoint for the service and region.
+            pub fn set_endpoint(&mut self, endpoint: Endpoint) {{
+                self.endpoint = Some(endpoint);
+            }}
+
+            /// Set the policy used to retry throttled requests and transient server errors.
+            pub fn set_retry_policy(&mut self, retry_policy: RetryPolicy) {{
+                self.retry_policy = Arc::new(retry_policy);
+            }}
+
+            /// Run every request made by this client through `interceptor`, after those added before it.
+            pub fn add_interceptor<I>(&mut self, interceptor: I) where I: Interceptor + 'static {{
+                self.interceptors.push(interceptor);
+            }}
             {methods}
         }}
+
+        #[cfg(feature = \"async\")]
+        impl<P, D> {type_name}<P, D> where P: ProvideAwsCredentials, D: DispatchSignedRequest + DispatchSignedRequestAsync + Clone + Send + 'static {{
+            {async_methods}
+        }}
         ",
         methods = protocol_generator.generate_methods(service),
+        async_methods = protocol_generator.generate_async_methods(service),
         service_name = match &service.metadata.service_abbreviation {
             &Some(ref service_abbreviation) => service_abbreviation.as_str(),
             &None => service.metadata.service_full_name.as_ref()
@@ -12,43 +12,51 @@ impl GenerateProtocol for QueryGenerator {
             format!(
                 "
                 {documentation}
-                {method_signature} {{
-                    let mut request = SignedRequest::new(\"{http_method}\", \"{endpoint_prefix}\", self.region, \"{request_uri}\");
-                    let mut params = Params::new();
-
-                    params.put(\"Action\", \"{operation_name}\");
-                    {serialize_input}
-                    request.set_params(params);
-
-                    request.sign(&try!(self.credentials_provider.credentials()));
-                    let result = try!(self.dispatcher.dispatch(&request));
-
-                    let mut reader = EventReader::from_str(&result.body);
-                    let mut stack = XmlResponse::new(reader.events().peekable());
-
-                    let _start_document = stack.next();
-                    let _response_envelope = stack.next();
+                {method_signature} -> Result<{output_type}, {error_type}> {{
+                    self.{method_name}_with_metadata({arguments}).map(|(output, _)| output)
+                }}
 
-                    match result.status {{
-                        200 => {{
-                            {method_return_value}
-                        }}
-                        _ => {{
-                            Err({error_type}::from_body(&result.body))
-                        }}
-                    }}
+                /// Like `{method_name}`, but also returns the metadata of the response.
+                {method_with_metadata_signature} -> Result<({output_type}, ResponseMetadata), {error_type}> {{
+                    {request}
+                    let result = try!(self.retry_policy.sign_and_dispatch(&self.dispatcher, &self.interceptors, &mut request, &try!(self.credentials_provider.credentials())));
 
+                    {response_handler}
                 }}
                 ",
+                arguments = generate_method_arguments(operation),
                 documentation = generate_documentation(operation),
                 error_type = operation.error_type_name(),
-                http_method = &operation.http.method,
-                endpoint_prefix = &service.metadata.endpoint_prefix,
-                method_return_value = generate_method_return_value(operation),
-                method_signature = generate_method_signature(operation),
-                operation_name = &operation.name,
-                request_uri = &operation.http.request_uri,
-                serialize_input = generate_method_input_serialization(operation),
+                method_name = operation.name.to_snake_case(),
+                method_signature = generate_method_signature(&operation.name.to_snake_case(), operation),
+                method_with_metadata_signature = generate_method_signature(&format!("{}_with_metadata", operation.name.to_snake_case()), operation),
+                output_type = &operation.output_shape_or("()"),
+                request = generate_request(service, operation),
+                response_handler = generate_response_handler(operation),
+            )
+        }).collect::<Vec<String>>().join("\n")
+    }
+
+    fn generate_async_methods(&self, service: &Service) -> String {
+        service.operations.values().map(|operation| {
+            format!(
+                "
+                /// Like `{method_name}`, but returns a future instead of blocking.
+                {method_signature} -> RusotoFuture<{output_type}, {error_type}> {{
+                    {request}
+                    Box::new(sign_and_dispatch_async(&self.credentials_provider, &self.dispatcher, &self.retry_policy, &self.interceptors, &mut request)
+                        .and_then(|result| -> Result<({output_type}, ResponseMetadata), {error_type}> {{
+                            {response_handler}
+                        }})
+                        .map(|(output, _)| output))
+                }}
+                ",
+                error_type = operation.error_type_name(),
+                method_name = operation.name.to_snake_case(),
+                method_signature = generate_method_signature(&format!("{}_async", operation.name.to_snake_case()), operation),
+                output_type = &operation.output_shape_or("()"),
+                request = generate_request(service, operation),
+                response_handler = generate_response_handler(operation),
             )
         }).collect::<Vec<String>>().join("\n")
     }
@@ -143,33 +151,83 @@ fn generate_method_input_serialization(operation: &Operation) -> String {
 fn generate_method_return_value(operation: &Operation) -> String {
     if operation.output.is_some() {
         format!(
-            "Ok(try!({output_type}Deserializer::deserialize(\"{output_type}\", &mut stack)))",
+            "match {output_type}Deserializer::deserialize(\"{output_type}\", &mut stack) {{
+                Ok(output) => Ok((output, metadata)),
+                Err(err) => Err({error_type}::from(err)),
+            }}",
             output_type = &operation.output.as_ref().unwrap().shape,
+            error_type = operation.error_type_name(),
         )
     } else {
-        "Ok(())".to_owned()
+        "Ok(((), metadata))".to_owned()
+    }
+}
+
+fn generate_method_arguments(operation: &Operation) -> &'static str {
+    if operation.input.is_some() {
+        "input"
+    } else {
+        ""
     }
 }
 
-fn generate_method_signature(operation: &Operation) -> String {
+fn generate_method_signature(method_name: &str, operation: &Operation) -> String {
     if operation.input.is_some() {
         format!(
-            "pub fn {operation_name}(&self, input: &{input_type}) -> Result<{output_type}, {error_type}>",
+            "pub fn {method_name}(&self, input: &{input_type})",
             input_type = operation.input.as_ref().unwrap().shape,
-            operation_name = operation.name.to_snake_case(),
-            output_type = &operation.output_shape_or("()"),
-            error_type = operation.error_type_name(),
+            method_name = method_name,
         )
     } else {
-        format!(
-            "pub fn {operation_name}(&self) -> Result<{output_type}, {error_type}>",
-            operation_name = operation.name.to_snake_case(),
-            output_type = &operation.output_shape_or("()"),
-            error_type = operation.error_type_name(),
-        )
+        format!("pub fn {method_name}(&self)", method_name = method_name)
     }
 }
 
+/// Builds `request`, ready to be signed and dispatched.
+fn generate_request(service: &Service, operation: &Operation) -> String {
+    format!(
+        "
+        let mut request = SignedRequest::new(\"{http_method}\", \"{endpoint_prefix}\", self.region.clone(), \"{request_uri}\");
+        request.set_endpoint(self.endpoint.clone());
+        let mut params = Params::new();
+
+        params.put(\"Action\", \"{operation_name}\");
+        {serialize_input}
+        request.set_params(params);
+        ",
+        http_method = &operation.http.method,
+        endpoint_prefix = &service.metadata.endpoint_prefix,
+        operation_name = &operation.name,
+        request_uri = &operation.http.request_uri,
+        serialize_input = generate_method_input_serialization(operation),
+    )
+}
+
+/// Turns `result` into the operation's result, along with the response's metadata.
+fn generate_response_handler(operation: &Operation) -> String {
+    format!(
+        "
+        let metadata = ResponseMetadata::from_response(&result);
+        let mut reader = EventReader::new(&result.body[..]);
+        let mut stack = XmlResponse::new(reader.events().peekable());
+
+        let _start_document = stack.next();
+        let _response_envelope = stack.next();
+
+        match result.status {{
+            200 => {{
+                {method_return_value}
+            }}
+            _ => {{
+                Err({error_type}::from_body(&String::from_utf8_lossy(&result.body), metadata))
+            }}
+        }}
+        ",
+        error_type = operation.error_type_name(),
+        method_return_value = generate_method_return_value(operation),
+    )
+}
+
 fn generate_deserializer_body(name: &str, shape: &Shape, service: &Service) -> String {
     match shape.shape_type {
         ShapeType::List => generate_list_deserializer(shape),
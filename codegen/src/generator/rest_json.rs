@@ -15,78 +15,56 @@ impl GenerateProtocol for RestJsonGenerator {
             // Retrieve the `Shape` for the input for this operation.
             let input_shape = service.shapes.get(input_type).unwrap();
 
-            // Construct a list of format strings which will be used to format
-            // the request URI, mapping the input struct to the URI arguments.
-            let member_uri_strings = generate_shape_member_uri_strings(input_shape);
-
-            // A boolean controlling whether or not the payload should be loaded
-            // into the request.
-            // According to the AWS SDK documentation, requests should only have
-            // a request body for operations with ANY non-URI or non-query
-            // parameters.
-            let load_payload = input_shape.members
-                .as_ref()
-                .unwrap()
-                .iter()
-                .any(|(_, member)| member.location.is_none());
-
-            // Construct a list of strings which will be used to load request
-            // parameters from the input struct into a `Params` vec, which will
-            // then be added to the request.
-            let member_param_strings = generate_shape_member_param_strings(input_shape);
-
             format!("
                 {documentation}
                 {method_signature} -> Result<{output_type}, {error_type}> {{
-                    {encode_input}
-
-                    {request_uri_formatter}
-
-                    let mut request = SignedRequest::new(\"{http_method}\", \"{endpoint_prefix}\", self.region, &request_uri);
-                    request.set_content_type(\"application/x-amz-json-1.1\".to_owned());
-                    {modify_endpoint_prefix}
-                    {load_payload}
-                    {load_params}
-
-                    request.sign(&try!(self.credentials_provider.credentials()));
+                    self.{method_name}_with_metadata({arguments}).map(|(output, _)| output)
+                }}
 
-                    let result = try!(self.dispatcher.dispatch(&request));
-                    let mut body = result.body;
+                /// Like `{method_name}`, but also returns the metadata of the response.
+                {method_with_metadata_signature} -> Result<({output_type}, ResponseMetadata), {error_type}> {{
+                    {request}
+                    let result = try!(self.retry_policy.sign_and_dispatch(&self.dispatcher, &self.interceptors, &mut request, &try!(self.credentials_provider.credentials())));
 
-                    // `serde-json` serializes field-less structs as \"null\", but AWS returns
-                    // \"{{}}\" for a field-less response, so we must check for this result
-                    // and convert it if necessary.
-                    if body == \"{{}}\" {{
-                        body = \"null\".to_owned();
-                    }}
+                    {response_handler}
+                }}
+                ",
+                documentation = generate_documentation(operation).unwrap_or("".to_owned()),
+                method_name = operation.name.to_snake_case(),
+                method_signature = generate_method_signature(&operation.name.to_snake_case(), operation, input_shape),
+                method_with_metadata_signature = generate_method_signature(&format!("{}_with_metadata", operation.name.to_snake_case()), operation, input_shape),
+                arguments = generate_method_arguments(input_shape),
+                error_type = operation.error_type_name(),
+                output_type = output_type,
+                request = generate_request(service, operation, input_shape),
+                response_handler = generate_response_handler(operation, output_type),
+            )
+        }).collect::<Vec<String>>().join("\n")
+    }
 
-                    debug!(\"Response body: {{}}\", body);
-                    debug!(\"Response status: {{}}\", result.status);
+    fn generate_async_methods(&self, service: &Service) -> String {
+        service.operations.values().map(|operation| {
+            let input_type = operation.input_shape();
+            let output_type = operation.output_shape_or("()");
+            let input_shape = service.shapes.get(input_type).unwrap();
 
-                    match result.status {{
-                        {status_code} => {{
-                            {ok_response}
-                        }}
-                         _ => Err({error_type}::from_body(&body)),
-                    }}
+            format!("
+                /// Like `{method_name}`, but returns a future instead of blocking.
+                {method_signature} -> RusotoFuture<{output_type}, {error_type}> {{
+                    {request}
+                    Box::new(sign_and_dispatch_async(&self.credentials_provider, &self.dispatcher, &self.retry_policy, &self.interceptors, &mut request)
+                        .and_then(|result| -> Result<({output_type}, ResponseMetadata), {error_type}> {{
+                            {response_handler}
+                        }})
+                        .map(|(output, _)| output))
                 }}
                 ",
-                documentation = generate_documentation(operation).unwrap_or("".to_owned()),
-                method_signature = generate_method_signature(operation, input_shape),
-                endpoint_prefix = service.signing_name(),
-                modify_endpoint_prefix = generate_endpoint_modification(service).unwrap_or("".to_owned()),
-                http_method = operation.http.method,
+                method_name = operation.name.to_snake_case(),
+                method_signature = generate_method_signature(&format!("{}_async", operation.name.to_snake_case()), operation, input_shape),
                 error_type = operation.error_type_name(),
-                status_code = operation.http.response_code.unwrap_or(200),
-                ok_response = generate_ok_response(operation, output_type),
                 output_type = output_type,
-                request_uri_formatter = generate_uri_formatter(
-                    &generate_snake_case_uri(&operation.http.request_uri),
-                    &member_uri_strings
-                ),
-                load_payload = generate_payload_loading_string(load_payload),
-                load_params = generate_params_loading_string(&member_param_strings),
-                encode_input = generate_encoding_string(load_payload),
+                request = generate_request(service, operation, input_shape),
+                response_handler = generate_response_handler(operation, output_type),
             )
         }).collect::<Vec<String>>().join("\n")
     }
@@ -140,16 +118,104 @@ fn generate_endpoint_modification(service: &Service) -> Option<String> {
 
 // IoT defines a lot of empty (and therefore unnecessary) request shapes
 // don't clutter method signatures with them
-fn generate_method_signature(operation: &Operation, shape: &Shape) -> String {
-    if shape.members.is_some() && shape.members.as_ref().unwrap().len() > 0 {
+fn generate_method_signature(method_name: &str, operation: &Operation, shape: &Shape) -> String {
+    if has_input(shape) {
         format!("pub fn {method_name}(&self, input: &{input_type})",
-            method_name = operation.name.to_snake_case(),
+            method_name = method_name,
             input_type = operation.input_shape())
     } else {
-        format!("pub fn {method_name}(&self)", method_name = operation.name.to_snake_case())
+        format!("pub fn {method_name}(&self)", method_name = method_name)
+    }
+}
+
+fn generate_method_arguments(shape: &Shape) -> &'static str {
+    if has_input(shape) {
+        "input"
+    } else {
+        ""
     }
 }
 
+fn has_input(shape: &Shape) -> bool {
+    shape.members.is_some() && shape.members.as_ref().unwrap().len() > 0
+}
+
+/// Builds `request`, ready to be signed and dispatched.
+fn generate_request(service: &Service, operation: &Operation, input_shape: &Shape) -> String {
+    // Construct a list of format strings which will be used to format
+    // the request URI, mapping the input struct to the URI arguments.
+    let member_uri_strings = generate_shape_member_uri_strings(input_shape);
+
+    // A boolean controlling whether or not the payload should be loaded
+    // into the request.
+    // According to the AWS SDK documentation, requests should only have
+    // a request body for operations with ANY non-URI or non-query
+    // parameters.
+    let load_payload = input_shape.members
+        .as_ref()
+        .unwrap()
+        .iter()
+        .any(|(_, member)| member.location.is_none());
+
+    // Construct a list of strings which will be used to load request
+    // parameters from the input struct into a `Params` vec, which will
+    // then be added to the request.
+    let member_param_strings = generate_shape_member_param_strings(input_shape);
+
+    format!("
+        {encode_input}
+
+        {request_uri_formatter}
+
+        let mut request = SignedRequest::new(\"{http_method}\", \"{endpoint_prefix}\", self.region.clone(), &request_uri);
+        request.set_endpoint(self.endpoint.clone());
+        request.set_content_type(\"application/x-amz-json-1.1\".to_owned());
+        {modify_endpoint_prefix}
+        {load_payload}
+        {load_params}
+        ",
+        endpoint_prefix = service.signing_name(),
+        modify_endpoint_prefix = generate_endpoint_modification(service).unwrap_or("".to_owned()),
+        http_method = operation.http.method,
+        request_uri_formatter = generate_uri_formatter(
+            &generate_snake_case_uri(&operation.http.request_uri),
+            &member_uri_strings
+        ),
+        load_payload = generate_payload_loading_string(load_payload),
+        load_params = generate_params_loading_string(&member_param_strings),
+        encode_input = generate_encoding_string(load_payload),
+    )
+}
+
+/// Turns `result` into the operation's result, along with the response's metadata.
+fn generate_response_handler(operation: &Operation, output_type: &str) -> String {
+    format!("
+        let metadata = ResponseMetadata::from_response(&result);
+        let mut body = result.body;
+
+        // `serde-json` serializes field-less structs as \"null\", but AWS returns
+        // \"{{}}\" for a field-less response, so we must check for this result
+        // and convert it if necessary.
+        if body == b\"{{}}\" {{
+            body = b\"null\".to_vec();
+        }}
+
+        debug!(\"Response body: {{}}\", String::from_utf8_lossy(&body));
+        debug!(\"Response status: {{}}\", result.status);
+
+        match result.status {{
+            {status_code} => {{
+                {ok_response}
+            }}
+             _ => Err({error_type}::from_body(&String::from_utf8_lossy(&body), metadata)),
+        }}
+        ",
+        error_type = operation.error_type_name(),
+        status_code = operation.http.response_code.unwrap_or(200),
+        ok_response = generate_ok_response(operation, output_type),
+    )
+}
+
 fn generate_encoding_string(load_payload: bool) -> String {
     if load_payload {
        "let encoded = serde_json::to_string(input).unwrap();".to_owned()
@@ -264,8 +330,8 @@ fn generate_documentation(operation: &Operation) -> Option<String> {
 
 fn generate_ok_response(operation: &Operation, output_type: &str) -> String {
     if operation.output.is_some() {
-        format!("Ok(serde_json::from_str::<{}>(&body).unwrap())", output_type)
+        format!("Ok((try!(serde_json::from_slice::<{}>(&body)), metadata))", output_type)
     } else {
-        "Ok(())".to_owned()
+        "Ok(((), metadata))".to_owned()
     }
 }
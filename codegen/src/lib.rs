@@ -20,10 +20,13 @@ use std::fs::File;
 use std::io::{Read, Write};
 use std::path::Path;
 
+use serde_json::Value;
+
 use botocore::Service as BotocoreService;
 use generator::generate_source;
 
 mod botocore;
+mod endpoints;
 mod generator;
 
 const BOTOCORE_DIR: &'static str = concat!(env!("CARGO_MANIFEST_DIR"), "/botocore/botocore/data/");
@@ -87,6 +90,38 @@ fn botocore_generate(input_path: &Path, output_path: &Path) {
     ));
 }
 
+/// Generate the tables of the endpoint resolver from botocore's `endpoints.json`.
+pub fn generate_endpoints(output_path: &Path) {
+    let input_path = Path::new(BOTOCORE_DIR).join("endpoints.json");
+    let mut input_file = File::open(&input_path).expect(&format!(
+        "{:?} not found",
+        input_path,
+    ));
+
+    let mut endpoints_data = String::new();
+
+    input_file.read_to_string(&mut endpoints_data).expect(&format!(
+        "Failed to read {:?}",
+        input_path,
+    ));
+
+    let endpoints: Value = serde_json::from_str(&endpoints_data).expect(&format!(
+        "Could not parse JSON in {:?}",
+        input_path,
+    ));
+
+    let destination_path = output_path.join("endpoints.rs");
+    let mut output_file = File::create(&destination_path).expect(&format!(
+        "Couldn't open file for writing: {:?}",
+        destination_path,
+    ));
+
+    output_file.write_all(endpoints::generate_source(&endpoints).as_bytes()).expect(&format!(
+        "Failed to write generated source code to {:?}",
+        destination_path,
+    ));
+}
+
 #[cfg(not(feature = "serde_macros"))]
 fn serde_generate(source: &Path, destination: &Path) {
     ::serde_codegen::expand(&source, &destination).unwrap();
@@ -0,0 +1,517 @@
+//! Opening the connections requests are sent over.
+
+use std::cell::Cell;
+use std::cmp;
+use std::error::Error;
+use std::fmt;
+use std::io::{self, Read, Write};
+use std::net::{Shutdown, SocketAddr, TcpStream, ToSocketAddrs};
+use std::time::{Duration, Instant};
+
+use hyper;
+use hyper::net::{HttpStream, HttpsStream, NetworkConnector, NetworkStream, Ssl};
+
+use proxy::ProxyConfig;
+
+thread_local! {
+    // the deadline of the request being sent on this thread, see `with_deadline`
+    static DEADLINE: Cell<Option<Instant>> = Cell::new(None);
+}
+
+/// Run `f`, making every connect, read and write on this thread's connections give up at
+/// `deadline`, on top of their own timeouts.
+pub fn with_deadline<F, T>(deadline: Option<Instant>, f: F) -> T where F: FnOnce() -> T {
+    struct Restore(Option<Instant>);
+
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            DEADLINE.with(|cell| cell.set(self.0));
+        }
+    }
+
+    let _restore = Restore(DEADLINE.with(|cell| cell.replace(deadline)));
+    f()
+}
+
+/// `timeout`, shortened to the time left until the deadline of the request on this thread.
+/// Fails with `TimedOut` once the deadline has passed.
+fn cap_timeout(timeout: Option<Duration>) -> io::Result<Option<Duration>> {
+    let deadline = match DEADLINE.with(|cell| cell.get()) {
+        Some(deadline) => deadline,
+        None => return Ok(timeout),
+    };
+
+    let now = Instant::now();
+    if now >= deadline {
+        return Err(io::Error::new(io::ErrorKind::TimedOut, "The deadline of the request has passed"));
+    }
+
+    let left = deadline - now;
+    Ok(Some(timeout.map_or(left, |timeout| cmp::min(timeout, left))))
+}
+
+/// A `hyper` connector with an optional connect timeout and proxies.
+///
+/// HTTPS connections through a proxy are tunnelled with `CONNECT`, so TLS is negotiated with
+/// the service itself and the proxy only ever sees encrypted traffic.  Plain HTTP requests are
+/// sent to the proxy itself, with the whole URL in their request line.  Connections honor the
+/// deadline set with `with_deadline`.
+pub struct Connector<S: Ssl> {
+    ssl: S,
+    proxies: Vec<ProxyConfig>,
+    connect_timeout: Option<Duration>,
+}
+
+impl <S: Ssl> Connector<S> {
+    pub fn new(ssl: S) -> Connector<S> {
+        Connector {
+            ssl: ssl,
+            proxies: Vec::new(),
+            connect_timeout: None,
+        }
+    }
+
+    /// Connect through `proxy`, instead of any proxies set before.
+    pub fn with_proxy(mut self, proxy: Option<ProxyConfig>) -> Connector<S> {
+        self.proxies = proxy.into_iter().collect();
+        self
+    }
+
+    /// Connect through the first of `proxies` that applies to the connection.
+    pub fn with_proxies(mut self, proxies: Vec<ProxyConfig>) -> Connector<S> {
+        self.proxies = proxies;
+        self
+    }
+
+    /// Give up on opening a TCP connection after `timeout`.  This includes the connection to
+    /// a proxy, and each read and write of the `CONNECT` exchange with it.
+    pub fn with_connect_timeout(mut self, timeout: Option<Duration>) -> Connector<S> {
+        self.connect_timeout = timeout;
+        self
+    }
+}
+
+impl <S: Ssl> NetworkConnector for Connector<S> {
+    type Stream = DeadlineStream<ProxiedStream<HttpsStream<S::Stream>>>;
+
+    fn connect(&self, host: &str, port: u16, scheme: &str) -> hyper::Result<Self::Stream> {
+        if scheme != "http" && scheme != "https" {
+            return Err(hyper::Error::from(io::Error::new(io::ErrorKind::InvalidInput, "Invalid scheme for connection")));
+        }
+
+        let connect_timeout = try!(cap_timeout(self.connect_timeout));
+        let proxy = self.proxies.iter().find(|proxy| proxy.applies_to(scheme, host));
+
+        let stream = match proxy {
+            Some(proxy) if scheme == "http" => {
+                debug!("Sending requests for {}:{} to proxy {}:{}", host, port, proxy.host(), proxy.port());
+                let stream = try!(connect_tcp(proxy.host(), proxy.port(), connect_timeout));
+                let origin = if port == 80 { format!("http://{}", host) } else { format!("http://{}:{}", host, port) };
+                let stream = ProxiedStream::absolute_form(HttpsStream::Http(stream), origin, proxy.authorization_header());
+                return Ok(DeadlineStream::new(stream));
+            }
+            Some(proxy) => {
+                debug!("Tunnelling to {}:{} through proxy {}:{}", host, port, proxy.host(), proxy.port());
+                let stream = try!(connect_tcp(proxy.host(), proxy.port(), connect_timeout));
+                try!(establish_tunnel(proxy, stream, host, port, self.connect_timeout))
+            }
+            None => try!(connect_tcp(host, port, connect_timeout)),
+        };
+
+        let stream = if scheme == "https" {
+            // the TLS handshake gets as long as connecting did
+            let handshake_timeout = try!(cap_timeout(self.connect_timeout));
+            try!(stream.0.set_read_timeout(handshake_timeout));
+            try!(stream.0.set_write_timeout(handshake_timeout));
+            try!(self.ssl.wrap_client(stream, host).map(HttpsStream::Https))
+        } else {
+            HttpsStream::Http(stream)
+        };
+        Ok(DeadlineStream::new(ProxiedStream::direct(stream)))
+    }
+}
+
+/// A connection to a service, or to a proxy that is sent its requests without a tunnel.
+///
+/// Such a proxy expects the whole URL in the request line, `GET http://host/path HTTP/1.1`,
+/// rather than just the path, and its `Proxy-Authorization` header with every request, so
+/// both are added to each request as it is written.
+pub struct ProxiedStream<S> {
+    inner: S,
+    absolute_form: Option<AbsoluteForm>,
+}
+
+impl <S> ProxiedStream<S> {
+    fn direct(inner: S) -> ProxiedStream<S> {
+        ProxiedStream {
+            inner: inner,
+            absolute_form: None,
+        }
+    }
+
+    fn absolute_form(inner: S, origin: String, authorization: Option<String>) -> ProxiedStream<S> {
+        ProxiedStream {
+            inner: inner,
+            absolute_form: Some(AbsoluteForm {
+                origin: origin,
+                authorization: authorization,
+                request_line: Vec::new(),
+                at_request_start: true,
+            }),
+        }
+    }
+}
+
+/// Rewrites the request lines of the requests written to a proxy.
+struct AbsoluteForm {
+    /// The scheme, host and port of the service.
+    origin: String,
+    /// The `Proxy-Authorization` header line, if the proxy needs one.
+    authorization: Option<String>,
+    /// The part of the current request line written so far.
+    request_line: Vec<u8>,
+    /// Whether the next write begins a request.  A response is read before the next request
+    /// on the connection is written.
+    at_request_start: bool,
+}
+
+impl AbsoluteForm {
+    fn write<W: Write>(&mut self, inner: &mut W, buf: &[u8]) -> io::Result<usize> {
+        if !self.at_request_start {
+            return inner.write(buf);
+        }
+
+        // hold on to the request line until it is complete
+        let end = buf.iter().position(|&byte| byte == b'\n').map_or(buf.len(), |newline| newline + 1);
+        self.request_line.extend_from_slice(&buf[..end]);
+        if self.request_line.ends_with(b"\n") {
+            try!(inner.write_all(&self.rewritten_request_line()));
+            self.request_line.clear();
+            self.at_request_start = false;
+        }
+        Ok(end)
+    }
+
+    fn rewritten_request_line(&self) -> Vec<u8> {
+        let line = String::from_utf8_lossy(&self.request_line).into_owned();
+        let mut rewritten = {
+            let mut parts = line.splitn(3, ' ');
+            match (parts.next(), parts.next(), parts.next()) {
+                (Some(method), Some(target), Some(version)) if target.starts_with('/') => {
+                    format!("{} {}{} {}", method, self.origin, target, version)
+                }
+                _ => line.clone(),
+            }
+        };
+        if let Some(ref authorization) = self.authorization {
+            rewritten.push_str(authorization);
+        }
+        rewritten.into_bytes()
+    }
+}
+
+impl <S: Read> Read for ProxiedStream<S> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if let Some(ref mut form) = self.absolute_form {
+            form.at_request_start = true;
+        }
+        self.inner.read(buf)
+    }
+}
+
+impl <S: Write> Write for ProxiedStream<S> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self.absolute_form {
+            Some(ref mut form) => form.write(&mut self.inner, buf),
+            None => self.inner.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+impl <S: NetworkStream> NetworkStream for ProxiedStream<S> {
+    fn peer_addr(&mut self) -> io::Result<SocketAddr> {
+        self.inner.peer_addr()
+    }
+
+    fn set_read_timeout(&self, timeout: Option<Duration>) -> io::Result<()> {
+        self.inner.set_read_timeout(timeout)
+    }
+
+    fn set_write_timeout(&self, timeout: Option<Duration>) -> io::Result<()> {
+        self.inner.set_write_timeout(timeout)
+    }
+
+    fn close(&mut self, how: Shutdown) -> io::Result<()> {
+        self.inner.close(how)
+    }
+}
+
+/// A connection whose reads and writes give up at the deadline of the request using it, as
+/// well as after the timeouts `hyper` sets.
+pub struct DeadlineStream<S> {
+    inner: S,
+    read_timeout: Cell<Option<Duration>>,
+    write_timeout: Cell<Option<Duration>>,
+}
+
+impl <S: NetworkStream> DeadlineStream<S> {
+    fn new(inner: S) -> DeadlineStream<S> {
+        DeadlineStream {
+            inner: inner,
+            read_timeout: Cell::new(None),
+            write_timeout: Cell::new(None),
+        }
+    }
+}
+
+impl <S: NetworkStream> Read for DeadlineStream<S> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        try!(self.inner.set_read_timeout(try!(cap_timeout(self.read_timeout.get()))));
+        self.inner.read(buf)
+    }
+}
+
+impl <S: NetworkStream> Write for DeadlineStream<S> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        try!(self.inner.set_write_timeout(try!(cap_timeout(self.write_timeout.get()))));
+        self.inner.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        try!(self.inner.set_write_timeout(try!(cap_timeout(self.write_timeout.get()))));
+        self.inner.flush()
+    }
+}
+
+impl <S: NetworkStream> NetworkStream for DeadlineStream<S> {
+    fn peer_addr(&mut self) -> io::Result<SocketAddr> {
+        self.inner.peer_addr()
+    }
+
+    // applied, shortened as needed, before every read or write
+    fn set_read_timeout(&self, timeout: Option<Duration>) -> io::Result<()> {
+        self.read_timeout.set(timeout);
+        Ok(())
+    }
+
+    fn set_write_timeout(&self, timeout: Option<Duration>) -> io::Result<()> {
+        self.write_timeout.set(timeout);
+        Ok(())
+    }
+
+    fn close(&mut self, how: Shutdown) -> io::Result<()> {
+        self.inner.close(how)
+    }
+}
+
+/// Open a tunnel to `host:port` through the proxy on the other end of `stream`, giving up on
+/// any read or write of the exchange after `timeout` or at the deadline.
+///
+/// Nothing has been sent to the service yet, so failures are marked as a `ConnectError`.
+fn establish_tunnel(proxy: &ProxyConfig, stream: HttpStream, host: &str, port: u16, timeout: Option<Duration>) -> io::Result<HttpStream> {
+    let mut tunnel = DeadlineStream::new(stream);
+    tunnel.read_timeout.set(timeout);
+    tunnel.write_timeout.set(timeout);
+
+    match proxy.establish_tunnel(&mut tunnel, host, port) {
+        Ok(()) => Ok(tunnel.inner),
+        Err(err) => Err(io::Error::new(err.kind(), ConnectError::new(proxy.host(), proxy.port(), err))),
+    }
+}
+
+/// A failure to resolve a host name, carried inside an `io::Error` so `HttpDispatchError` can
+/// tell it apart from other IO errors.
+#[derive(Debug)]
+pub struct ResolveError {
+    message: String,
+    cause: io::Error,
+}
+
+impl ResolveError {
+    pub fn new(host: &str, cause: io::Error) -> ResolveError {
+        ResolveError {
+            message: format!("Could not resolve {}: {}", host, cause),
+            cause: cause,
+        }
+    }
+}
+
+impl Error for ResolveError {
+    fn description(&self) -> &str {
+        &self.message
+    }
+
+    fn cause(&self) -> Option<&Error> {
+        Some(&self.cause)
+    }
+}
+
+impl fmt::Display for ResolveError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+/// A failure to open a TCP connection, carried inside an `io::Error` so `HttpDispatchError`
+/// can tell that the request was never sent.
+#[derive(Debug)]
+pub struct ConnectError {
+    message: String,
+    cause: io::Error,
+}
+
+impl ConnectError {
+    pub fn new(host: &str, port: u16, cause: io::Error) -> ConnectError {
+        ConnectError {
+            message: format!("Could not connect to {}:{}: {}", host, port, cause),
+            cause: cause,
+        }
+    }
+}
+
+impl Error for ConnectError {
+    fn description(&self) -> &str {
+        &self.message
+    }
+
+    fn cause(&self) -> Option<&Error> {
+        Some(&self.cause)
+    }
+}
+
+impl fmt::Display for ConnectError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+fn resolve(host: &str, port: u16) -> io::Result<Vec<SocketAddr>> {
+    (host, port).to_socket_addrs()
+        .map(|addresses| addresses.collect())
+        .map_err(|err| io::Error::new(err.kind(), ResolveError::new(host, err)))
+}
+
+fn connect_tcp(host: &str, port: u16, timeout: Option<Duration>) -> hyper::Result<HttpStream> {
+    let mut last_error = None;
+
+    // try every address the host resolves to, as TcpStream::connect would
+    for address in try!(resolve(host, port)) {
+        let result = match timeout {
+            Some(timeout) => TcpStream::connect_timeout(&address, timeout),
+            None => TcpStream::connect(address),
+        };
+        match result {
+            Ok(stream) => return Ok(HttpStream(stream)),
+            Err(err) => last_error = Some(err),
+        }
+    }
+
+    Err(hyper::Error::from(match last_error {
+        Some(err) => io::Error::new(err.kind(), ConnectError::new(host, port, err)),
+        None => {
+            let cause = io::Error::new(io::ErrorKind::NotFound, "no addresses found");
+            io::Error::new(io::ErrorKind::NotFound, ResolveError::new(host, cause))
+        }
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{ErrorKind, Read, Write};
+    use std::net::TcpListener;
+    use std::thread;
+    use std::time::{Duration, Instant};
+
+    use hyper::Client;
+    use hyper::Error as HyperError;
+    use hyper::net::Openssl;
+
+    use proxy::ProxyConfig;
+    use super::{connect_tcp, establish_tunnel, resolve, with_deadline, ConnectError, Connector, ResolveError};
+
+    #[test]
+    fn connect_with_timeout() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        assert!(connect_tcp("127.0.0.1", port, Some(Duration::from_secs(5))).is_ok());
+
+        drop(listener);
+        match connect_tcp("127.0.0.1", port, Some(Duration::from_secs(5))) {
+            Err(HyperError::Io(ref err)) => {
+                assert_eq!(err.kind(), ErrorKind::ConnectionRefused);
+                assert!(err.get_ref().map_or(false, |inner| inner.is::<ConnectError>()));
+            }
+            _ => panic!("expected the connection to be refused"),
+        }
+    }
+
+    #[test]
+    fn stalled_proxies_time_out() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        // accept connections, but never answer CONNECT
+        thread::spawn(move || {
+            let _first = listener.accept().unwrap();
+            let _second = listener.accept().unwrap();
+            thread::sleep(Duration::from_secs(10));
+        });
+
+        let proxy = ProxyConfig::new("127.0.0.1", port);
+
+        let stream = connect_tcp("127.0.0.1", port, None).unwrap();
+        let err = establish_tunnel(&proxy, stream, "sqs.us-east-1.amazonaws.com", 443, Some(Duration::from_millis(200))).unwrap_err();
+        assert!(err.kind() == ErrorKind::TimedOut || err.kind() == ErrorKind::WouldBlock);
+        assert!(err.get_ref().map_or(false, |inner| inner.is::<ConnectError>()));
+
+        // without a timeout of its own, the exchange still ends at the deadline
+        let started = Instant::now();
+        let stream = connect_tcp("127.0.0.1", port, None).unwrap();
+        let result = with_deadline(Some(started + Duration::from_millis(200)), || {
+            establish_tunnel(&proxy, stream, "sqs.us-east-1.amazonaws.com", 443, None)
+        });
+        assert!(result.is_err());
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+
+    #[test]
+    fn plain_http_goes_to_the_proxy_in_absolute_form() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        let proxy = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = Vec::new();
+            let mut byte = [0; 1];
+            while !request.ends_with(b"\r\n\r\n") {
+                stream.read(&mut byte).unwrap();
+                request.push(byte[0]);
+            }
+            stream.write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 2\r\nConnection: close\r\n\r\nok").unwrap();
+            String::from_utf8(request).unwrap()
+        });
+
+        // the service's host is never resolved, only the proxy is connected to
+        let proxy_config = ProxyConfig::new("127.0.0.1", port).with_basic_auth("user", "secret");
+        let client = Client::with_connector(Connector::new(Openssl::default()).with_proxy(Some(proxy_config)));
+        let mut response = client.get("http://dynamodb.local:8000/?Action=ListTables").send().unwrap();
+        let mut body = String::new();
+        response.read_to_string(&mut body).unwrap();
+        assert_eq!(body, "ok");
+
+        let request = proxy.join().unwrap();
+        assert!(request.starts_with("GET http://dynamodb.local:8000/?Action=ListTables HTTP/1.1\r\n"));
+        assert!(request.contains("Proxy-Authorization: Basic dXNlcjpzZWNyZXQ=\r\n"));
+    }
+
+    #[test]
+    fn resolve_errors_are_marked() {
+        let err = resolve("nonexistent.invalid", 443).unwrap_err();
+        assert!(err.get_ref().map_or(false, |inner| inner.is::<ResolveError>()));
+    }
+}
@@ -14,13 +14,14 @@ use std::ascii::AsciiExt;
 use std::collections::HashMap;
 use std::sync::Mutex;
 use std::cell::RefCell;
-use hyper::Client;
 use hyper::header::Connection;
 use regex::Regex;
 use chrono::{Duration, UTC, DateTime, ParseError};
 use serde_json::{Value, from_str};
 use std::time::Duration as StdDuration;
 
+use request::HttpConfig;
+
 /// AWS API access credentials, including access key, secret key, token (for IAM profiles), and
 /// expiration timestamp.
 #[derive(Clone, Debug)]
@@ -314,8 +315,11 @@ impl ProvideAwsCredentials for IamProvider {
 	
 		// TODO: backoff and retry on failure.
         let mut address : String = "http://169.254.169.254/latest/meta-data/iam/security-credentials".to_string();
-        let mut client = Client::new();
-        client.set_read_timeout(Some(StdDuration::from_secs(15)));
+        // the metadata service is only reachable on EC2, so fail fast everywhere else
+        let client = HttpConfig::default()
+            .with_connect_timeout(Some(StdDuration::from_secs(2)))
+            .with_read_timeout(Some(StdDuration::from_secs(15)))
+            .client();
         let mut response;
         match client.get(&address)
             .header(Connection::close()).send() {
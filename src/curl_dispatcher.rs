@@ -0,0 +1,389 @@
+//! Non-blocking request dispatch on an event loop, using libcurl.
+
+use std::cmp;
+use std::collections::HashMap;
+use std::io;
+use std::mem;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use curl;
+use curl::easy::{Easy, List};
+use futures::{Future, Stream};
+use futures::future;
+use futures::sync::{mpsc, oneshot};
+use tokio_core::reactor::Core;
+use tokio_curl::Session;
+
+use future::{DispatchSignedRequestAsync, RusotoFuture};
+use request::{DEFAULT_USER_AGENT, DispatchSignedRequest, HttpConfig, HttpDispatchError, HttpDispatchErrorKind, HttpResponse,
+              check_region_endpoint, request_uri};
+use signature::SignedRequest;
+
+/// Sends requests from a single event loop thread, so that any number of them can be in
+/// flight without a thread each.
+///
+/// Transfers are driven by libcurl's multi interface, which also keeps connections open for
+/// later requests to the same host.  Clones share the event loop, which stops once the last
+/// of them has been dropped.
+///
+/// The blocking `dispatch` waits for the response on the calling thread.
+#[derive(Clone)]
+pub struct CurlDispatcher {
+    config: HttpConfig,
+    transfers: mpsc::UnboundedSender<Transfer>,
+}
+
+/// A request handed to the event loop, and where to put its response.
+struct Transfer {
+    easy: Easy,
+    uri: String,
+    body: Arc<Mutex<Vec<u8>>>,
+    headers: Arc<Mutex<HashMap<String, String>>>,
+    complete: oneshot::Sender<Result<HttpResponse, HttpDispatchError>>,
+}
+
+impl CurlDispatcher {
+    /// Start an event loop with the default `HttpConfig`, going through the proxies configured
+    /// with `HTTPS_PROXY` for HTTPS, `HTTP_PROXY` for plain HTTP and `NO_PROXY`, if any.
+    pub fn new() -> io::Result<CurlDispatcher> {
+        CurlDispatcher::with_config(HttpConfig::from_env())
+    }
+
+    /// Start an event loop sending requests with the timeouts and proxies of `config`.
+    ///
+    /// libcurl has no timeout for a single read or write, so a transfer is instead abandoned
+    /// once no data at all has moved, in either direction, for as long as the shorter of the
+    /// read and write timeouts.
+    pub fn with_config(config: HttpConfig) -> io::Result<CurlDispatcher> {
+        let (transfers, incoming) = mpsc::unbounded::<Transfer>();
+        let (started, ready) = ::std::sync::mpsc::channel();
+
+        try!(thread::Builder::new().name("rusoto-event-loop".to_owned()).spawn(move || {
+            let mut core = match Core::new() {
+                Ok(core) => core,
+                Err(err) => {
+                    let _ = started.send(Err(err));
+                    return;
+                }
+            };
+            let _ = started.send(Ok(()));
+
+            let handle = core.handle();
+            let session = Session::new(handle.clone());
+            let _ = core.run(incoming.for_each(|transfer| {
+                handle.spawn(perform(&session, transfer));
+                Ok(())
+            }));
+        }));
+
+        match ready.recv() {
+            Ok(result) => try!(result),
+            Err(_) => return Err(io::Error::new(io::ErrorKind::Other, "The event loop thread exited before it started")),
+        }
+
+        Ok(CurlDispatcher {
+            config: config,
+            transfers: transfers,
+        })
+    }
+
+    /// Set up a libcurl handle that sends `request`, collecting the response in `body` and
+    /// `headers`.
+    fn easy(&self, request: &SignedRequest, uri: &str, body: Arc<Mutex<Vec<u8>>>, headers: Arc<Mutex<HashMap<String, String>>>)
+        -> Result<Easy, curl::Error>
+    {
+        let mut easy = Easy::new();
+        try!(easy.url(uri));
+
+        match request.method() {
+            "GET" => try!(easy.get(true)),
+            "HEAD" => try!(easy.nobody(true)),
+            method => try!(easy.custom_request(method)),
+        }
+        if let Some(payload) = request.payload() {
+            try!(easy.post_fields_copy(payload));
+        }
+
+        let mut list = List::new();
+        for (name, values) in request.headers() {
+            for value in values {
+                try!(list.append(&format!("{}: {}", name, String::from_utf8_lossy(value))));
+            }
+        }
+        if !request.headers().contains_key("user-agent") {
+            try!(list.append(&format!("user-agent: {}", String::from_utf8_lossy(&DEFAULT_USER_AGENT[0]))));
+        }
+        // libcurl adds these unless told not to, and AWS doesn't expect either
+        try!(list.append("Expect:"));
+        if !request.headers().contains_key("content-type") {
+            try!(list.append("Content-Type:"));
+        }
+        try!(easy.http_headers(list));
+
+        if let Some(timeout) = self.config.connect_timeout() {
+            try!(easy.connect_timeout(timeout));
+        }
+        let stall_timeout = match (self.config.read_timeout(), self.config.write_timeout()) {
+            (Some(read), Some(write)) => Some(cmp::min(read, write)),
+            (read, write) => read.or(write),
+        };
+        if let Some(timeout) = stall_timeout {
+            try!(easy.low_speed_limit(1));
+            try!(easy.low_speed_time(timeout));
+        }
+        if let Some(deadline) = request.deadline() {
+            // libcurl fails the transfer with a timeout once this much time has passed in all;
+            // zero would mean no limit, so a deadline that has passed gets the shortest one
+            let now = Instant::now();
+            let left = if deadline > now { deadline - now } else { Duration::from_millis(1) };
+            try!(easy.timeout(left));
+        }
+
+        let hostname = request.hostname();
+        let host = hostname.split(':').next().unwrap_or("");
+        match self.config.proxy_for(request.scheme(), host) {
+            Some(proxy) => {
+                try!(easy.proxy(&format!("http://{}:{}", proxy.host(), proxy.port())));
+                // plain HTTP requests are sent to the proxy itself, naming the whole URL
+                try!(easy.http_proxy_tunnel(request.scheme() == "https"));
+                if let Some((username, password)) = proxy.basic_auth() {
+                    try!(easy.proxy_username(username));
+                    try!(easy.proxy_password(password));
+                }
+            }
+            // libcurl reads the proxy environment variables itself, which mustn't override
+            // the configuration
+            _ => try!(easy.noproxy("*")),
+        }
+
+        try!(easy.write_function(move |data| {
+            body.lock().unwrap().extend_from_slice(data);
+            Ok(data.len())
+        }));
+        try!(easy.header_function(move |line| {
+            let line = String::from_utf8_lossy(line);
+            let mut headers = headers.lock().unwrap();
+            if line.starts_with("HTTP/") {
+                // the status line of a new response, such as the one after a 100 Continue
+                headers.clear();
+            } else if let Some(colon) = line.find(':') {
+                headers.insert(line[..colon].trim().to_owned(), line[colon + 1..].trim().to_owned());
+            }
+            true
+        }));
+
+        Ok(easy)
+    }
+}
+
+impl DispatchSignedRequestAsync for CurlDispatcher {
+    fn dispatch_async(&self, request: &SignedRequest) -> RusotoFuture<HttpResponse, HttpDispatchError> {
+        if let Err(err) = check_region_endpoint(request) {
+            return Box::new(future::err(err));
+        }
+
+        let uri = request_uri(request);
+
+        if request.payload_stream().is_some() {
+            return Box::new(future::err(HttpDispatchError::new(HttpDispatchErrorKind::InvalidRequest,
+                "Requests with streamed payloads can't be dispatched asynchronously").with_uri(uri)));
+        }
+
+        let body = Arc::new(Mutex::new(Vec::new()));
+        let headers = Arc::new(Mutex::new(HashMap::new()));
+        let easy = match self.easy(request, &uri, body.clone(), headers.clone()) {
+            Ok(easy) => easy,
+            Err(err) => return Box::new(future::err(curl_error(err).with_uri(uri))),
+        };
+
+        debug!("Sending {} request to {}", request.method(), uri);
+
+        let (complete, response) = oneshot::channel();
+        let transfer = Transfer {
+            easy: easy,
+            uri: uri.clone(),
+            body: body,
+            headers: headers,
+            complete: complete,
+        };
+        if self.transfers.unbounded_send(transfer).is_err() {
+            return Box::new(future::err(HttpDispatchError::new(HttpDispatchErrorKind::Other, "The event loop has stopped").with_uri(uri)));
+        }
+
+        Box::new(response.then(move |result| {
+            match result {
+                Ok(result) => result,
+                Err(_) => Err(HttpDispatchError::new(HttpDispatchErrorKind::Other,
+                    "The event loop stopped before the response arrived").with_uri(uri)),
+            }
+        }))
+    }
+}
+
+impl DispatchSignedRequest for CurlDispatcher {
+    fn dispatch(&self, request: &SignedRequest) -> Result<HttpResponse, HttpDispatchError> {
+        self.dispatch_async(request).wait()
+    }
+}
+
+/// Run `transfer` on the event loop `session` belongs to, completing it once the response
+/// has arrived.
+fn perform(session: &Session, transfer: Transfer) -> Box<Future<Item=(), Error=()>> {
+    let Transfer { easy, uri, body, headers, complete } = transfer;
+
+    Box::new(session.perform(easy).then(move |result| {
+        let result = match result {
+            Ok(mut easy) => {
+                easy.response_code().map_err(curl_error).map(|status| {
+                    HttpResponse {
+                        status: status as u16,
+                        body: mem::replace(&mut *body.lock().unwrap(), Vec::new()),
+                        headers: mem::replace(&mut *headers.lock().unwrap(), HashMap::new()),
+                    }
+                })
+            }
+            Err(err) => Err(io_error(err.into_error())),
+        };
+
+        // the caller may have dropped the future, in which case nobody wants the response
+        let _ = complete.send(result.map_err(|err| err.with_uri(uri)));
+        Ok(())
+    }))
+}
+
+/// Classify an error libcurl reported for a transfer.
+fn curl_error(err: curl::Error) -> HttpDispatchError {
+    let kind = if err.is_operation_timedout() {
+        HttpDispatchErrorKind::Timeout
+    } else if err.is_couldnt_resolve_host() || err.is_couldnt_resolve_proxy() {
+        HttpDispatchErrorKind::Dns
+    } else if err.is_couldnt_connect() {
+        HttpDispatchErrorKind::ConnectionRefused
+    } else if err.is_ssl_connect_error() || err.is_peer_failed_verification() || err.is_ssl_certproblem() || err.is_ssl_cacert() {
+        HttpDispatchErrorKind::Tls
+    } else if err.is_send_error() || err.is_recv_error() || err.is_got_nothing() || err.is_partial_file() {
+        HttpDispatchErrorKind::ConnectionReset
+    } else if err.is_url_malformed() || err.is_unsupported_protocol() {
+        HttpDispatchErrorKind::InvalidRequest
+    } else {
+        HttpDispatchErrorKind::Other
+    };
+
+    // libcurl doesn't say whether a timeout or reset happened before the request was
+    // written, so only failures to resolve the host or connect to it are safe to retry
+    let sent = match kind {
+        HttpDispatchErrorKind::Dns | HttpDispatchErrorKind::ConnectionRefused |
+            HttpDispatchErrorKind::InvalidRequest => false,
+        _ => true,
+    };
+
+    let message = err.to_string();
+    let dispatch_error = HttpDispatchError::new(kind, message).with_cause(err);
+    if sent { dispatch_error.with_request_sent() } else { dispatch_error }
+}
+
+/// Classify an error from the event loop, which usually carries the libcurl error.
+fn io_error(err: io::Error) -> HttpDispatchError {
+    if err.get_ref().map_or(false, |inner| inner.is::<curl::Error>()) {
+        if let Some(Ok(err)) = err.into_inner().map(|inner| inner.downcast::<curl::Error>()) {
+            return curl_error(*err);
+        }
+        return HttpDispatchError::new(HttpDispatchErrorKind::Other, "libcurl failed");
+    }
+
+    HttpDispatchError::from(err)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::TcpListener;
+    use std::thread;
+
+    use futures::Future;
+    use futures::future::join_all;
+
+    use future::DispatchSignedRequestAsync;
+    use region::Region;
+    use request::{DispatchSignedRequest, HttpConfig, HttpDispatchErrorKind};
+    use signature::SignedRequest;
+    use super::*;
+
+    /// Accept `connections` connections, read a request from each, and only then answer
+    /// them all, each with its own number as the body.
+    fn serve(connections: usize) -> u16 {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        thread::spawn(move || {
+            let mut streams = Vec::new();
+            for _ in 0..connections {
+                let (stream, _) = listener.accept().unwrap();
+                let mut reader = BufReader::new(stream);
+                let mut line = String::new();
+                while reader.read_line(&mut line).unwrap() > 0 && line != "\r\n" {
+                    line.clear();
+                }
+                streams.push(reader.into_inner());
+            }
+
+            for (number, mut stream) in streams.into_iter().enumerate() {
+                let body = number.to_string();
+                write!(stream, "HTTP/1.1 200 OK\r\nx-amzn-RequestId: request-{}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                       number, body.len(), body).unwrap();
+            }
+        });
+
+        port
+    }
+
+    fn request_to(port: u16) -> SignedRequest<'static> {
+        let mut request = SignedRequest::new("GET", "sqs", Region::UsEast1, "/");
+        request.set_endpoint(Some(format!("http://127.0.0.1:{}", port).parse().unwrap()));
+        request
+    }
+
+    #[test]
+    fn dispatch_to_local_server() {
+        let port = serve(1);
+        let dispatcher = CurlDispatcher::with_config(HttpConfig::default()).unwrap();
+
+        let response = dispatcher.dispatch(&request_to(port)).unwrap();
+
+        assert_eq!(response.status, 200);
+        assert_eq!(response.body, b"0".to_vec());
+        assert_eq!(response.headers["x-amzn-RequestId"], "request-0");
+    }
+
+    #[test]
+    fn requests_are_in_flight_together() {
+        // the server only answers once every request has arrived, so this would never finish
+        // if requests were sent one at a time
+        let port = serve(8);
+        let dispatcher = CurlDispatcher::with_config(HttpConfig::default()).unwrap();
+
+        let responses = join_all((0..8).map(|_| dispatcher.dispatch_async(&request_to(port))).collect::<Vec<_>>())
+            .wait()
+            .unwrap();
+
+        assert_eq!(responses.len(), 8);
+        assert!(responses.iter().all(|response| response.status == 200));
+    }
+
+    #[test]
+    fn connection_errors_are_classified() {
+        let port = {
+            let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+            listener.local_addr().unwrap().port()
+        };
+        let dispatcher = CurlDispatcher::with_config(HttpConfig::default()).unwrap();
+
+        let err = dispatcher.dispatch(&request_to(port)).unwrap_err();
+
+        assert_eq!(err.kind(), HttpDispatchErrorKind::ConnectionRefused);
+        assert!(err.is_retryable());
+        assert_eq!(err.uri(), Some(&format!("http://127.0.0.1:{}/", port)[..]));
+    }
+}
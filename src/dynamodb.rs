@@ -1,3 +1,45 @@
 //! Amazon DynamoDB
 
 include!(concat!(env!("OUT_DIR"), "/dynamodb.rs"));
+
+#[cfg(test)]
+mod tests {
+    use error::AwsError;
+    use mock::{MockCredentialsProvider, MockRequestDispatcher};
+    use region::Region;
+    use retry::RetryPolicy;
+    use super::*;
+
+    fn client_returning(status: u16, body: &str) -> DynamoDbClient<MockCredentialsProvider, MockRequestDispatcher> {
+        let dispatcher = MockRequestDispatcher::with_status(status).with_body(body);
+        let mut client = DynamoDbClient::with_request_dispatcher(dispatcher, MockCredentialsProvider, Region::UsEast1);
+        client.set_retry_policy(RetryPolicy::default().with_max_attempts(1));
+        client
+    }
+
+    #[test]
+    fn namespaced_error_types() {
+        let client = client_returning(400,
+            "{\"__type\":\"com.amazonaws.dynamodb.v20120810#ResourceNotFoundException\",\"message\":\"Requested resource not found\"}");
+        let request = DescribeTableInput { table_name: "missing".to_owned() };
+
+        match client.describe_table(&request) {
+            Err(ref err @ DescribeTableError::ResourceNotFound(..)) => {
+                assert_eq!(err.code(), Some("ResourceNotFoundException"));
+                assert!(err.is_client_error());
+            }
+            err @ _ => panic!("Expected ResourceNotFound, got {:?}", err),
+        }
+
+        let client = client_returning(400,
+            "{\"__type\":\"com.amazonaws.dynamodb.v20120810#ThrottlingException\",\"message\":\"Rate of requests exceeds the allowed throughput.\"}");
+
+        match client.describe_table(&request) {
+            Err(ref err @ DescribeTableError::Unknown(_)) => {
+                assert_eq!(err.code(), Some("ThrottlingException"));
+                assert!(err.is_throttling() && err.is_retryable() && !err.is_client_error());
+            }
+            err @ _ => panic!("Expected an unknown throttling error, got {:?}", err),
+        }
+    }
+}
@@ -0,0 +1,152 @@
+//! Custom service endpoints.
+//!
+//! Lets a client talk to something other than the default AWS endpoint for its service and
+//! region, such as DynamoDB Local, ElasticMQ or minio.
+
+use std::error::Error;
+use std::fmt::{Display, Error as FmtError, Formatter};
+use std::str::FromStr;
+
+use url::Url;
+
+use region::Region;
+
+/// The location of a service: a scheme, a host and optionally a port.
+///
+/// Requests are still signed with the client's region unless a signing region is given.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Endpoint {
+    scheme: String,
+    host: String,
+    port: Option<u16>,
+    signing_region: Option<Region>,
+}
+
+/// An error produced when attempting to convert a `str` into an `Endpoint` fails.
+#[derive(Debug, PartialEq)]
+pub struct ParseEndpointError {
+    message: String,
+}
+
+impl Endpoint {
+    /// An HTTPS endpoint on the default port of `host`.
+    pub fn new<S>(host: S) -> Endpoint where S: Into<String> {
+        Endpoint {
+            scheme: "https".to_owned(),
+            host: host.into(),
+            port: None,
+            signing_region: None,
+        }
+    }
+
+    /// Set the scheme, `http` or `https`.
+    pub fn with_scheme<S>(mut self, scheme: S) -> Endpoint where S: Into<String> {
+        self.scheme = scheme.into();
+        self
+    }
+
+    pub fn with_port(mut self, port: u16) -> Endpoint {
+        self.port = Some(port);
+        self
+    }
+
+    /// Sign requests for `region` instead of the client's region.
+    pub fn with_signing_region(mut self, region: Region) -> Endpoint {
+        self.signing_region = Some(region);
+        self
+    }
+
+    pub fn scheme(&self) -> &str {
+        &self.scheme
+    }
+
+    pub fn host(&self) -> &str {
+        &self.host
+    }
+
+    pub fn port(&self) -> Option<u16> {
+        self.port
+    }
+
+    pub fn signing_region(&self) -> Option<Region> {
+        self.signing_region.clone()
+    }
+
+    /// The host and, if one was given, the port, as used in the `Host` header.
+    pub fn authority(&self) -> String {
+        match self.port {
+            Some(port) => format!("{}:{}", self.host, port),
+            None => self.host.clone(),
+        }
+    }
+}
+
+impl Display for Endpoint {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), FmtError> {
+        write!(f, "{}://{}", self.scheme, self.authority())
+    }
+}
+
+impl FromStr for Endpoint {
+    type Err = ParseEndpointError;
+
+    /// Parse a URL such as `http://localhost:8000`.  Any path is ignored.
+    fn from_str(s: &str) -> Result<Endpoint, ParseEndpointError> {
+        let url = try!(Url::parse(s).map_err(|_| ParseEndpointError::new(s)));
+
+        if url.scheme() != "http" && url.scheme() != "https" {
+            return Err(ParseEndpointError::new(s));
+        }
+
+        let host = match url.host_str() {
+            Some(host) => host.to_owned(),
+            None => return Err(ParseEndpointError::new(s)),
+        };
+
+        Ok(Endpoint {
+            scheme: url.scheme().to_owned(),
+            host: host,
+            port: url.port(),
+            signing_region: None,
+        })
+    }
+}
+
+impl ParseEndpointError {
+    pub fn new(input: &str) -> Self {
+        ParseEndpointError {
+            message: format!("Not a valid endpoint URL: {}", input)
+        }
+    }
+}
+
+impl Error for ParseEndpointError {
+    fn description(&self) -> &str {
+        &self.message
+    }
+}
+
+impl Display for ParseEndpointError {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), FmtError> {
+        write!(f, "{}", self.message)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_str() {
+        let endpoint: Endpoint = "http://localhost:8000".parse().unwrap();
+        assert_eq!(endpoint, Endpoint::new("localhost").with_scheme("http").with_port(8000));
+        assert_eq!(endpoint.to_string(), "http://localhost:8000");
+
+        let endpoint: Endpoint = "https://minio.example.com/".parse().unwrap();
+        assert_eq!(endpoint.authority(), "minio.example.com");
+        assert_eq!(endpoint.scheme(), "https");
+
+        assert!("localhost:8000".parse::<Endpoint>().is_err());
+        assert!("ftp://localhost".parse::<Endpoint>().is_err());
+    }
+}
@@ -0,0 +1,204 @@
+//! The default endpoints of services, generated from botocore's `endpoints.json`.
+//!
+//! Endpoints are grouped in partitions, such as `aws`, `aws-cn` and `aws-us-gov`, each with its
+//! own DNS suffix and regions.  A region the tables don't know yet still resolves, to the
+//! default endpoint of the partition whose region pattern it matches.
+
+use std::collections::HashMap;
+
+use regex::Regex;
+
+include!(concat!(env!("OUT_DIR"), "/endpoints.rs"));
+
+// every request resolves its endpoint, so the region patterns are only compiled once
+lazy_static! {
+    static ref REGION_REGEXES: HashMap<&'static str, Regex> = PARTITIONS.iter()
+        .filter_map(|partition| Regex::new(partition.region_regex).ok().map(|regex| (partition.name, regex)))
+        .collect();
+}
+
+/// Where requests for a service in a region are sent and how they are signed.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ResolvedEndpoint {
+    /// The partition the endpoint is in, such as `aws` or `aws-cn`.
+    pub partition: &'static str,
+    pub hostname: String,
+    /// The protocols the endpoint accepts, `https` and sometimes `http`.
+    pub protocols: &'static [&'static str],
+    /// The signature versions the endpoint accepts, such as `v4`.
+    pub signature_versions: &'static [&'static str],
+    /// The region of the credential scope requests are signed with.  For global endpoints,
+    /// such as IAM's, this isn't the region the client was created for.
+    pub signing_region: String,
+    /// The service of the credential scope requests are signed with.
+    pub signing_service: String,
+}
+
+struct Partition {
+    name: &'static str,
+    dns_suffix: &'static str,
+    region_regex: &'static str,
+    regions: &'static [&'static str],
+    defaults: EndpointData,
+    services: &'static [ServiceEndpoints],
+}
+
+struct ServiceEndpoints {
+    name: &'static str,
+    partition_endpoint: Option<&'static str>,
+    is_regionalized: bool,
+    defaults: EndpointData,
+    endpoints: &'static [(&'static str, EndpointData)],
+}
+
+/// What an endpoint, a service or a partition says about endpoints.  Anything left out of an
+/// endpoint is taken from its service, then from its partition.
+#[derive(Clone, Copy)]
+struct EndpointData {
+    hostname: Option<&'static str>,
+    protocols: Option<&'static [&'static str]>,
+    signature_versions: Option<&'static [&'static str]>,
+    credential_scope_region: Option<&'static str>,
+    credential_scope_service: Option<&'static str>,
+}
+
+impl EndpointData {
+    fn or(self, defaults: EndpointData) -> EndpointData {
+        EndpointData {
+            hostname: self.hostname.or(defaults.hostname),
+            protocols: self.protocols.or(defaults.protocols),
+            signature_versions: self.signature_versions.or(defaults.signature_versions),
+            credential_scope_region: self.credential_scope_region.or(defaults.credential_scope_region),
+            credential_scope_service: self.credential_scope_service.or(defaults.credential_scope_service),
+        }
+    }
+}
+
+impl Partition {
+    fn service(&self, name: &str) -> Option<&ServiceEndpoints> {
+        self.services.binary_search_by(|service| service.name.cmp(name))
+            .ok()
+            .map(|index| &self.services[index])
+    }
+
+    fn matches(&self, region: &str) -> bool {
+        self.regions.iter().any(|known| *known == region) ||
+            REGION_REGEXES.get(self.name).map_or(false, |regex| regex.is_match(region))
+    }
+
+    fn resolve(&self, service_name: &str, service: Option<&ServiceEndpoints>, endpoint_name: &str) -> ResolvedEndpoint {
+        let mut data = service.and_then(|service| service.endpoint(endpoint_name))
+            .unwrap_or(EMPTY_ENDPOINT_DATA);
+        if let Some(service) = service {
+            data = data.or(service.defaults);
+        }
+        data = data.or(self.defaults);
+
+        let hostname = data.hostname.unwrap_or("{service}.{region}.{dnsSuffix}")
+            .replace("{service}", service_name)
+            .replace("{region}", endpoint_name)
+            .replace("{dnsSuffix}", self.dns_suffix);
+
+        ResolvedEndpoint {
+            partition: self.name,
+            hostname: hostname,
+            protocols: data.protocols.unwrap_or(DEFAULT_PROTOCOLS),
+            signature_versions: data.signature_versions.unwrap_or(DEFAULT_SIGNATURE_VERSIONS),
+            signing_region: data.credential_scope_region.unwrap_or(endpoint_name).to_owned(),
+            signing_service: data.credential_scope_service.unwrap_or(service_name).to_owned(),
+        }
+    }
+}
+
+impl ServiceEndpoints {
+    fn endpoint(&self, name: &str) -> Option<EndpointData> {
+        self.endpoints.binary_search_by(|&(endpoint, _)| endpoint.cmp(name))
+            .ok()
+            .map(|index| self.endpoints[index].1)
+    }
+}
+
+const DEFAULT_PROTOCOLS: &'static [&'static str] = &["https"];
+const DEFAULT_SIGNATURE_VERSIONS: &'static [&'static str] = &["v4"];
+
+const EMPTY_ENDPOINT_DATA: EndpointData = EndpointData {
+    hostname: None,
+    protocols: None,
+    signature_versions: None,
+    credential_scope_region: None,
+    credential_scope_service: None,
+};
+
+/// Resolve the endpoint of `service`, given by its endpoint prefix such as `dynamodb`, in
+/// `region`.
+///
+/// Services with a single global endpoint, such as IAM and Route 53, resolve to it whatever
+/// the region in their partition.  `None` is returned only if `region` matches no partition.
+pub fn resolve_endpoint(service: &str, region: &str) -> Option<ResolvedEndpoint> {
+    for partition in PARTITIONS {
+        let service_endpoints = partition.service(service);
+
+        if service_endpoints.map_or(false, |endpoints| endpoints.endpoint(region).is_some()) {
+            return Some(partition.resolve(service, service_endpoints, region));
+        }
+
+        if partition.matches(region) {
+            let endpoint_name = match service_endpoints {
+                Some(endpoints) if !endpoints.is_regionalized => endpoints.partition_endpoint.unwrap_or(region),
+                _ => region,
+            };
+            return Some(partition.resolve(service, service_endpoints, endpoint_name));
+        }
+    }
+
+    None
+}
+
+/// Resolve the endpoint of a service that isn't tied to a region, such as `iam`, `route53` or
+/// `cloudfront`, in `partition`.  `None` is returned if the service has no global endpoint there.
+pub fn resolve_global_endpoint(service: &str, partition: &str) -> Option<ResolvedEndpoint> {
+    PARTITIONS.iter()
+        .find(|candidate| candidate.name == partition)
+        .and_then(|partition| {
+            partition.service(service)
+                .and_then(|endpoints| endpoints.partition_endpoint)
+                .map(|endpoint_name| partition.resolve(service, partition.service(service), endpoint_name))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn regional_endpoints() {
+        let endpoint = resolve_endpoint("dynamodb", "eu-west-1").unwrap();
+        assert_eq!(endpoint.partition, "aws");
+        assert_eq!(endpoint.hostname, "dynamodb.eu-west-1.amazonaws.com");
+        assert_eq!(endpoint.signing_region, "eu-west-1");
+        assert_eq!(endpoint.signing_service, "dynamodb");
+        assert!(endpoint.protocols.contains(&"https"));
+
+        assert_eq!(resolve_endpoint("sqs", "cn-north-1").unwrap().hostname, "sqs.cn-north-1.amazonaws.com.cn");
+        assert_eq!(resolve_endpoint("sqs", "us-gov-west-1").unwrap().partition, "aws-us-gov");
+    }
+
+    #[test]
+    fn global_endpoints() {
+        let endpoint = resolve_endpoint("iam", "eu-west-1").unwrap();
+        assert_eq!(endpoint.hostname, "iam.amazonaws.com");
+        assert_eq!(endpoint.signing_region, "us-east-1");
+
+        assert_eq!(resolve_global_endpoint("iam", "aws"), Some(endpoint));
+        assert_eq!(resolve_global_endpoint("route53", "aws").unwrap().hostname, "route53.amazonaws.com");
+        assert_eq!(resolve_global_endpoint("dynamodb", "aws"), None);
+    }
+
+    #[test]
+    fn unknown_regions_and_services() {
+        // a region that matches the pattern of a partition gets its default endpoint
+        assert_eq!(resolve_endpoint("sqs", "us-north-9").unwrap().hostname, "sqs.us-north-9.amazonaws.com");
+        assert_eq!(resolve_endpoint("newservice", "eu-west-1").unwrap().hostname, "newservice.eu-west-1.amazonaws.com");
+        assert_eq!(resolve_endpoint("sqs", "mars-1"), None);
+    }
+}
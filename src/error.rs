@@ -0,0 +1,173 @@
+//! What every error returned by an operation has in common.
+
+use std::error::Error;
+
+use metadata::ResponseMetadata;
+use request::HttpDispatchError;
+use retry::is_throttling_code;
+
+/// An error response that didn't match any of the errors modeled for the operation, such as
+/// one with a code AWS introduced after the client was generated.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct UnknownError {
+    /// The `__type` of a JSON error or the `Code` of an XML one, if the body had one.
+    pub code: Option<String>,
+    /// The message of the error, or the whole body if it couldn't be parsed.
+    pub message: String,
+    /// The HTTP status of the response.
+    pub status: u16,
+    /// The body of the response, as it was received.
+    pub body: String,
+    /// The metadata of the response.
+    pub metadata: ResponseMetadata,
+}
+
+impl UnknownError {
+    pub fn new<S>(code: Option<&str>, message: S, body: &str, metadata: ResponseMetadata) -> UnknownError
+        where S: Into<String>
+    {
+        UnknownError {
+            code: code.and_then(|code| if code.is_empty() { None } else { Some(code.to_owned()) }),
+            message: message.into(),
+            status: metadata.status,
+            body: body.to_owned(),
+            metadata: metadata,
+        }
+    }
+}
+
+/// Implemented by the error of every operation, so that retry, alerting or metrics code can
+/// handle errors from any service the same way.
+pub trait AwsError: Error {
+    /// The error code AWS sent, such as `ResourceNotFoundException` or `NoSuchKey`, if the
+    /// error was read from a response and had one.
+    fn code(&self) -> Option<&str>;
+
+    /// A description of the error, which for errors read from a response is the message AWS
+    /// sent.
+    fn message(&self) -> &str {
+        self.description()
+    }
+
+    /// The HTTP status of the response the error was read from, if there was one.
+    fn status(&self) -> Option<u16>;
+
+    /// The error sending the request or receiving its response, if that's what went wrong.
+    fn http_dispatch_error(&self) -> Option<&HttpDispatchError>;
+
+    /// Whether AWS refused the request because too many were being made.
+    fn is_throttling(&self) -> bool {
+        self.code().map_or(false, is_throttling_code)
+    }
+
+    /// Whether AWS blamed the request, with a 4xx status.  Throttling errors are not client
+    /// errors, even though most of them have a 4xx status.
+    fn is_client_error(&self) -> bool {
+        self.status().map_or(false, |status| status >= 400 && status < 500) && !self.is_throttling()
+    }
+
+    /// Whether AWS blamed itself, with a 5xx status.  Throttling errors are not server errors,
+    /// even though some of them have a 503 status.
+    fn is_server_error(&self) -> bool {
+        self.status().map_or(false, |status| status >= 500) && !self.is_throttling()
+    }
+
+    /// Whether making the request again might succeed: throttling errors, server errors and
+    /// failures to send the request other than it being invalid are.
+    fn is_retryable(&self) -> bool {
+        match self.http_dispatch_error() {
+            Some(dispatch_error) => dispatch_error.is_retryable(),
+            None => self.is_throttling() || self.is_server_error(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::error::Error;
+    use std::fmt;
+
+    use metadata::ResponseMetadata;
+    use request::{HttpDispatchError, HttpDispatchErrorKind};
+    use super::*;
+
+    #[derive(Debug)]
+    enum TestError {
+        Service(&'static str, u16),
+        HttpDispatch(HttpDispatchError),
+    }
+
+    impl fmt::Display for TestError {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "{}", self.description())
+        }
+    }
+
+    impl Error for TestError {
+        fn description(&self) -> &str {
+            "test error"
+        }
+    }
+
+    impl AwsError for TestError {
+        fn code(&self) -> Option<&str> {
+            match *self {
+                TestError::Service(code, _) => Some(code),
+                TestError::HttpDispatch(_) => None,
+            }
+        }
+
+        fn status(&self) -> Option<u16> {
+            match *self {
+                TestError::Service(_, status) => Some(status),
+                TestError::HttpDispatch(_) => None,
+            }
+        }
+
+        fn http_dispatch_error(&self) -> Option<&HttpDispatchError> {
+            match *self {
+                TestError::HttpDispatch(ref dispatch_error) => Some(dispatch_error),
+                TestError::Service(..) => None,
+            }
+        }
+    }
+
+    #[test]
+    fn classification() {
+        let throttled = TestError::Service("ThrottlingException", 400);
+        assert!(throttled.is_throttling() && !throttled.is_client_error() && throttled.is_retryable());
+
+        let slow_down = TestError::Service("SlowDown", 503);
+        assert!(slow_down.is_throttling() && !slow_down.is_server_error() && slow_down.is_retryable());
+
+        let not_found = TestError::Service("ResourceNotFoundException", 400);
+        assert!(not_found.is_client_error() && !not_found.is_retryable());
+        assert_eq!(not_found.message(), "test error");
+
+        let internal = TestError::Service("InternalFailure", 500);
+        assert!(internal.is_server_error() && internal.is_retryable());
+
+        let reset = TestError::HttpDispatch(HttpDispatchError::new(HttpDispatchErrorKind::ConnectionReset, "reset"));
+        assert!(reset.is_retryable() && !reset.is_client_error() && !reset.is_server_error());
+
+        let invalid = TestError::HttpDispatch(HttpDispatchError::new(HttpDispatchErrorKind::InvalidRequest, "bad uri"));
+        assert!(!invalid.is_retryable());
+    }
+
+    #[test]
+    fn unknown_errors_keep_the_response() {
+        let mut metadata = ResponseMetadata::default();
+        metadata.status = 400;
+        let body = "{\"__type\":\"NewFangledException\",\"message\":\"Something new\"}";
+
+        let unknown = UnknownError::new(Some("NewFangledException"), "Something new", body, metadata.clone());
+        assert_eq!(unknown.code, Some("NewFangledException".to_owned()));
+        assert_eq!(unknown.message, "Something new");
+        assert_eq!(unknown.status, 400);
+        assert_eq!(unknown.body, body);
+        assert_eq!(unknown.metadata, metadata);
+
+        let unknown = UnknownError::new(Some(""), "<html>Bad Gateway</html>", "<html>Bad Gateway</html>", metadata);
+        assert_eq!(unknown.code, None);
+    }
+}
@@ -0,0 +1,219 @@
+//! Injecting faults into requests, to test how code copes with AWS misbehaving.
+
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+
+use rand::{self, Rng};
+
+use request::{DispatchSignedRequest, HttpDispatchError, HttpDispatchErrorKind, HttpResponse};
+use signature::SignedRequest;
+
+/// Something that can go wrong with a request.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Fault {
+    /// Wait this long before sending the request.
+    Latency(Duration),
+    /// Answer with this status, usually 500 or 503, and an internal error body, without
+    /// sending the request.
+    ServerError(u16),
+    /// Answer with the service's throttling error, without sending the request.
+    Throttling,
+    /// Fail with a connection reset, without sending the request.
+    ConnectionReset,
+    /// Send the request, but cut the body of its response in half.
+    TruncatedBody,
+}
+
+/// Wraps another dispatcher and makes some of the requests sent with it fail.
+///
+/// Faults are taken from the script first, one per request, and once it's used up they are
+/// picked at random according to their probabilities.  Requests without a fault are sent
+/// unchanged.
+///
+/// Error bodies are shaped like the service's own: JSON for JSON protocol services, and the
+/// XML of S3, EC2 or the query protocol otherwise, so they parse into the usual error types.
+pub struct FaultInjectingDispatcher<D> {
+    dispatcher: D,
+    script: Mutex<VecDeque<Option<Fault>>>,
+    faults: Vec<(f64, Fault)>,
+}
+
+impl <D> FaultInjectingDispatcher<D> where D: DispatchSignedRequest {
+    pub fn new(dispatcher: D) -> FaultInjectingDispatcher<D> {
+        FaultInjectingDispatcher {
+            dispatcher: dispatcher,
+            script: Mutex::new(VecDeque::new()),
+            faults: Vec::new(),
+        }
+    }
+
+    /// Give a request `fault` with `probability`, between 0 and 1.  A request gets at most one
+    /// fault, so the probabilities of all the faults shouldn't add up to more than 1.
+    pub fn with_fault(mut self, probability: f64, fault: Fault) -> FaultInjectingDispatcher<D> {
+        self.faults.push((probability, fault));
+        self
+    }
+
+    /// Give the next requests these faults, in order.  `None` sends a request unchanged.
+    pub fn with_script(self, script: Vec<Option<Fault>>) -> FaultInjectingDispatcher<D> {
+        self.script.lock().unwrap().extend(script);
+        self
+    }
+
+    fn next_fault(&self) -> Option<Fault> {
+        if let Some(scripted) = self.script.lock().unwrap().pop_front() {
+            return scripted;
+        }
+
+        let mut roll = rand::thread_rng().next_f64();
+        for &(probability, ref fault) in &self.faults {
+            if roll < probability {
+                return Some(fault.clone());
+            }
+            roll -= probability;
+        }
+
+        None
+    }
+}
+
+impl <D> DispatchSignedRequest for FaultInjectingDispatcher<D> where D: DispatchSignedRequest {
+    fn dispatch(&self, request: &SignedRequest) -> Result<HttpResponse, HttpDispatchError> {
+        let fault = match self.next_fault() {
+            Some(fault) => fault,
+            None => return self.dispatcher.dispatch(request),
+        };
+
+        debug!("Injecting {:?} into {} {}", fault, request.method(), request.path());
+
+        match fault {
+            Fault::Latency(delay) => {
+                thread::sleep(delay);
+                self.dispatcher.dispatch(request)
+            }
+            Fault::ServerError(status) => {
+                let code = if status == 503 { "ServiceUnavailable" } else { "InternalFailure" };
+                Ok(error_response(request, status, code, "The service encountered an internal error"))
+            }
+            Fault::Throttling => Ok(throttling_response(request)),
+            Fault::ConnectionReset => {
+                Err(HttpDispatchError::new(HttpDispatchErrorKind::ConnectionReset, "Connection reset by peer"))
+            }
+            Fault::TruncatedBody => {
+                let mut response = try!(self.dispatcher.dispatch(request));
+                let length = response.body.len() / 2;
+                response.body.truncate(length);
+                Ok(response)
+            }
+        }
+    }
+}
+
+fn is_json(request: &SignedRequest) -> bool {
+    request.content_type.as_ref().map_or(false, |content_type| content_type.contains("json"))
+}
+
+fn throttling_response(request: &SignedRequest) -> HttpResponse {
+    match &request.service[..] {
+        "s3" => error_response(request, 503, "SlowDown", "Please reduce your request rate."),
+        "ec2" => error_response(request, 503, "RequestLimitExceeded", "Request limit exceeded."),
+        _ if is_json(request) => error_response(request, 400, "ThrottlingException", "Rate exceeded"),
+        _ => error_response(request, 400, "Throttling", "Rate exceeded"),
+    }
+}
+
+fn error_response(request: &SignedRequest, status: u16, code: &str, message: &str) -> HttpResponse {
+    let body = if is_json(request) {
+        format!("{{\"__type\":\"{}\",\"message\":\"{}\"}}", code, message)
+    } else {
+        match &request.service[..] {
+            "s3" => format!("<Error><Code>{}</Code><Message>{}</Message></Error>", code, message),
+            "ec2" => format!("<Response><Errors><Error><Code>{}</Code><Message>{}</Message></Error></Errors></Response>", code, message),
+            _ => format!("<ErrorResponse><Error><Type>{}</Type><Code>{}</Code><Message>{}</Message></Error></ErrorResponse>",
+                if status < 500 { "Sender" } else { "Receiver" }, code, message),
+        }
+    };
+
+    let mut response = HttpResponse::default();
+    response.status = status;
+    response.body = body.into_bytes();
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use chrono::{Duration as ChronoDuration, UTC};
+
+    use credential::AwsCredentials;
+    use interceptor::InterceptorChain;
+    use mock::MockRequestDispatcher;
+    use region::Region;
+    use request::{DispatchSignedRequest, HttpDispatchErrorKind};
+    use retry::{RetryPolicy, error_code};
+    use signature::SignedRequest;
+    use super::*;
+
+    fn json_request<'a>() -> SignedRequest<'a> {
+        let mut request = SignedRequest::new("POST", "dynamodb", Region::UsEast1, "/");
+        request.set_content_type("application/x-amz-json-1.0".to_owned());
+        request
+    }
+
+    #[test]
+    fn error_bodies_match_the_protocol() {
+        let dispatcher = FaultInjectingDispatcher::new(MockRequestDispatcher::with_status(200))
+            .with_fault(1.0, Fault::Throttling);
+
+        let response = dispatcher.dispatch(&json_request()).unwrap();
+        assert_eq!(response.status, 400);
+        assert_eq!(error_code(&String::from_utf8_lossy(&response.body)), Some("ThrottlingException".to_owned()));
+
+        let response = dispatcher.dispatch(&SignedRequest::new("POST", "sqs", Region::UsEast1, "/")).unwrap();
+        assert_eq!(error_code(&String::from_utf8_lossy(&response.body)), Some("Throttling".to_owned()));
+
+        let response = dispatcher.dispatch(&SignedRequest::new("GET", "s3", Region::UsEast1, "/bucket")).unwrap();
+        assert_eq!(response.status, 503);
+        assert_eq!(error_code(&String::from_utf8_lossy(&response.body)), Some("SlowDown".to_owned()));
+    }
+
+    #[test]
+    fn scripted_faults() {
+        let dispatcher = FaultInjectingDispatcher::new(MockRequestDispatcher::with_status(200).with_body("0123456789"))
+            .with_script(vec![
+                Some(Fault::ConnectionReset),
+                Some(Fault::TruncatedBody),
+                Some(Fault::Latency(Duration::from_millis(20))),
+                None,
+            ]);
+        let request = json_request();
+
+        match dispatcher.dispatch(&request) {
+            Err(err) => assert_eq!(err.kind(), HttpDispatchErrorKind::ConnectionReset),
+            Ok(_) => panic!("expected the connection to be reset"),
+        }
+        assert_eq!(dispatcher.dispatch(&request).unwrap().body, b"01234".to_vec());
+
+        let started = Instant::now();
+        assert_eq!(dispatcher.dispatch(&request).unwrap().body, b"0123456789".to_vec());
+        assert!(started.elapsed() >= Duration::from_millis(20));
+
+        assert_eq!(dispatcher.dispatch(&request).unwrap().status, 200);
+    }
+
+    #[test]
+    fn retries_recover_from_faults() {
+        let dispatcher = FaultInjectingDispatcher::new(MockRequestDispatcher::with_status(200))
+            .with_script(vec![Some(Fault::ServerError(503)), Some(Fault::Throttling)])
+            .with_fault(0.0, Fault::ConnectionReset);
+        let policy = RetryPolicy::default().with_base_delay(Duration::from_millis(0));
+        let credentials = AwsCredentials::new("key", "secret", None, UTC::now() + ChronoDuration::seconds(600));
+
+        let mut request = json_request();
+        let response = policy.sign_and_dispatch(&dispatcher, &InterceptorChain::new(), &mut request, &credentials).unwrap();
+        assert_eq!(response.status, 200);
+    }
+}
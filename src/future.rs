@@ -0,0 +1,357 @@
+//! Asynchronous request dispatch.
+//!
+//! Every client has an `_async` variant of each of its operations, available when its
+//! dispatcher implements `DispatchSignedRequestAsync`.  Requests are signed on the calling
+//! thread exactly as they are for the blocking operations, and are retried by the client's
+//! `RetryPolicy` the same way; only sending them, waiting for the response and waiting
+//! between attempts is deferred to the returned future.
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::time::Instant;
+
+use futures::Future;
+use futures::future::{self, Loop, loop_fn};
+use futures_cpupool::CpuPool;
+use tokio_timer::Timer;
+
+use credential::{AwsCredentials, CredentialsError, ProvideAwsCredentials};
+use endpoint::Endpoint;
+use interceptor::InterceptorChain;
+use param::Params;
+use region::Region;
+use request::{DispatchSignedRequest, HttpResponse, HttpStreamingResponse, HttpDispatchError, HttpDispatchErrorKind};
+use retry::{RetryPolicy, past_deadline};
+use signature::{PayloadSigning, SignedRequest};
+
+// waits out the delays between attempts for every client, on a thread of its own
+lazy_static! {
+    static ref RETRY_TIMER: Timer = Timer::default();
+}
+
+/// The future returned by asynchronous operations.
+pub type RusotoFuture<T, E> = Box<Future<Item=T, Error=E> + Send>;
+
+/// Sends signed requests without blocking the calling thread.
+pub trait DispatchSignedRequestAsync {
+    /// Send `request`, resolving to its response.
+    ///
+    /// The request is only borrowed for the duration of the call, so implementations must
+    /// copy whatever they need from it before returning.
+    fn dispatch_async(&self, request: &SignedRequest) -> RusotoFuture<HttpResponse, HttpDispatchError>;
+}
+
+/// Sign `request` with credentials from `provider` and send it with `dispatcher`, running
+/// it through `interceptors` and retrying it as `retry_policy` says, like the blocking
+/// operations do.
+///
+/// Retries are signed and sent after this has returned, so the dispatcher is cloned into
+/// the returned future.
+pub fn sign_and_dispatch_async<P, D, E>(provider: &P, dispatcher: &D, retry_policy: &Arc<RetryPolicy>, interceptors: &InterceptorChain, request: &mut SignedRequest)
+    -> RusotoFuture<HttpResponse, E>
+    where P: ProvideAwsCredentials,
+          D: DispatchSignedRequestAsync + Clone + Send + 'static,
+          E: From<CredentialsError> + From<HttpDispatchError> + Send + 'static
+{
+    // a streamed payload is read as it is sent, so it can't be copied into the future
+    if request.payload_stream.is_some() {
+        return Box::new(future::err(E::from(HttpDispatchError::new(HttpDispatchErrorKind::InvalidRequest,
+            "Requests with streamed payloads can't be dispatched asynchronously"))));
+    }
+
+    let credentials = match provider.credentials() {
+        Ok(credentials) => credentials,
+        Err(err) => return Box::new(future::err(E::from(err))),
+    };
+
+    if request.deadline().is_none() {
+        request.set_deadline(retry_policy.call_deadline());
+    }
+
+    // once per call, so every attempt signs the same request
+    if let Some(result) = interceptors.before_signing(request) {
+        return Box::new(future::result(result.map_err(E::from)));
+    }
+
+    let dispatcher = dispatcher.clone();
+    let policy = retry_policy.clone();
+    let interceptors = interceptors.clone();
+    let request = OwnedRequest::from(&*request);
+    let deadline = request.deadline;
+
+    Box::new(loop_fn(0, move |retry| {
+        let policy = policy.clone();
+
+        send_attempt(&dispatcher, &interceptors, &request, &credentials).then(move |result| -> RusotoFuture<Loop<HttpResponse, u32>, HttpDispatchError> {
+            let retryable = match result {
+                Ok(ref response) => policy.should_retry(response),
+                Err(ref err) => err.is_retryable(),
+            };
+            let delay = policy.delay(retry);
+
+            if !retryable || retry + 1 >= policy.max_attempts() || past_deadline(deadline, delay) {
+                return Box::new(future::result(result.map(Loop::Break)));
+            }
+            match result {
+                Ok(ref response) => debug!("Retrying request after status {}, waiting {:?}", response.status, delay),
+                Err(ref err) => debug!("Retrying request after dispatch error \"{}\", waiting {:?}", err, delay),
+            }
+
+            // a timer error only means the delay was cut short
+            Box::new(RETRY_TIMER.sleep(delay).then(move |_| Ok(Loop::Continue(retry + 1))))
+        })
+    }).map_err(E::from))
+}
+
+/// Sign a copy of `request` and send it once, calling the hooks `interceptors` run for every
+/// attempt.
+fn send_attempt<D>(dispatcher: &D, interceptors: &InterceptorChain, request: &OwnedRequest, credentials: &AwsCredentials)
+    -> RusotoFuture<HttpResponse, HttpDispatchError> where D: DispatchSignedRequestAsync
+{
+    // signing a fresh copy refreshes x-amz-date, so retries aren't rejected as stale
+    let mut signed = request.as_signed_request();
+    if let Some(result) = interceptors.sign(&mut signed, credentials) {
+        return Box::new(future::result(result));
+    }
+
+    let response = dispatcher.dispatch_async(&signed);
+    if interceptors.is_empty() {
+        return response;
+    }
+
+    // the response hooks run once the request itself is gone, so they get a copy
+    let interceptors = interceptors.clone();
+    let sent = OwnedRequest::from(&signed);
+    Box::new(response.then(move |mut result| {
+        interceptors.after_response(&sent.as_signed_request(), &mut result);
+        result
+    }))
+}
+
+/// Runs a blocking dispatcher on a pool of threads, so that clients built with it get
+/// asynchronous operations as well as blocking ones.
+///
+/// At most one request per thread is in flight at any time; the rest wait in the pool's
+/// queue.  This suits dispatchers that can only block, such as those used in tests; to
+/// have many requests in flight at once, use `CurlDispatcher` instead.
+pub struct ThreadPoolDispatcher<D> {
+    dispatcher: Arc<D>,
+    pool: CpuPool,
+}
+
+impl <D> Clone for ThreadPoolDispatcher<D> {
+    fn clone(&self) -> ThreadPoolDispatcher<D> {
+        ThreadPoolDispatcher {
+            dispatcher: self.dispatcher.clone(),
+            pool: self.pool.clone(),
+        }
+    }
+}
+
+impl <D> ThreadPoolDispatcher<D> where D: DispatchSignedRequest + Send + Sync + 'static {
+    /// Dispatch requests on a new pool of `threads` threads.
+    pub fn new(dispatcher: D, threads: usize) -> ThreadPoolDispatcher<D> {
+        ThreadPoolDispatcher::with_pool(dispatcher, CpuPool::new(threads))
+    }
+
+    /// Dispatch requests on an existing pool.
+    pub fn with_pool(dispatcher: D, pool: CpuPool) -> ThreadPoolDispatcher<D> {
+        ThreadPoolDispatcher {
+            dispatcher: Arc::new(dispatcher),
+            pool: pool,
+        }
+    }
+}
+
+impl <D> DispatchSignedRequest for ThreadPoolDispatcher<D> where D: DispatchSignedRequest {
+    fn dispatch(&self, request: &SignedRequest) -> Result<HttpResponse, HttpDispatchError> {
+        self.dispatcher.dispatch(request)
+    }
+
+    fn dispatch_streaming(&self, request: &SignedRequest) -> Result<HttpStreamingResponse, HttpDispatchError> {
+        self.dispatcher.dispatch_streaming(request)
+    }
+}
+
+impl <D> DispatchSignedRequestAsync for ThreadPoolDispatcher<D> where D: DispatchSignedRequest + Send + Sync + 'static {
+    fn dispatch_async(&self, request: &SignedRequest) -> RusotoFuture<HttpResponse, HttpDispatchError> {
+        // a streamed payload is read as it is sent, and the reader can't follow the request
+        // onto another thread
+        if request.payload_stream.is_some() {
+            return Box::new(future::err(HttpDispatchError::new(HttpDispatchErrorKind::InvalidRequest,
+                "Requests with streamed payloads can't be dispatched asynchronously")));
+        }
+
+        let request = OwnedRequest::from(request);
+        let dispatcher = self.dispatcher.clone();
+
+        Box::new(self.pool.spawn_fn(move || dispatcher.dispatch(&request.as_signed_request())))
+    }
+}
+
+/// A copy of a signed request that can be sent to another thread.
+struct OwnedRequest {
+    method: String,
+    service: String,
+    region: Region,
+    path: String,
+    headers: BTreeMap<String, Vec<Vec<u8>>>,
+    params: Params,
+    hostname: Option<String>,
+    endpoint: Option<Endpoint>,
+    payload: Option<Vec<u8>>,
+    payload_signing: PayloadSigning,
+    content_type: Option<String>,
+    canonical_query_string: String,
+    canonical_uri: String,
+    deadline: Option<Instant>,
+    sign_security_token: bool,
+}
+
+impl <'a, 'b> From<&'a SignedRequest<'b>> for OwnedRequest {
+    fn from(request: &'a SignedRequest<'b>) -> OwnedRequest {
+        OwnedRequest {
+            method: request.method.clone(),
+            service: request.service.clone(),
+            region: request.region.clone(),
+            path: request.path.clone(),
+            headers: request.headers.clone(),
+            params: request.params.clone(),
+            hostname: request.hostname.clone(),
+            endpoint: request.endpoint.clone(),
+            payload: request.payload.map(|payload| payload.to_vec()),
+            payload_signing: request.payload_signing.clone(),
+            content_type: request.content_type.clone(),
+            canonical_query_string: request.canonical_query_string.clone(),
+            canonical_uri: request.canonical_uri.clone(),
+            deadline: request.deadline,
+            sign_security_token: request.sign_security_token,
+        }
+    }
+}
+
+impl OwnedRequest {
+    fn as_signed_request(&self) -> SignedRequest {
+        SignedRequest {
+            method: self.method.clone(),
+            service: self.service.clone(),
+            region: self.region.clone(),
+            path: self.path.clone(),
+            headers: self.headers.clone(),
+            params: self.params.clone(),
+            hostname: self.hostname.clone(),
+            endpoint: self.endpoint.clone(),
+            payload: self.payload.as_ref().map(|payload| &payload[..]),
+            payload_stream: None,
+            payload_signing: self.payload_signing.clone(),
+            content_type: self.content_type.clone(),
+            canonical_query_string: self.canonical_query_string.clone(),
+            canonical_uri: self.canonical_uri.clone(),
+            deadline: self.deadline,
+            sign_security_token: self.sign_security_token,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+
+    use futures::Future;
+
+    use super::*;
+    use credential::CredentialsError;
+    use interceptor::InterceptorChain;
+    use mock::{MockCredentialsProvider, RequestMatcher, ScriptedDispatcher};
+    use region::Region;
+    use request::{DispatchSignedRequest, HttpResponse, HttpDispatchError};
+    use retry::{RetryPolicy, past_deadline};
+    use signature::SignedRequest;
+
+    #[derive(Debug)]
+    enum TestError {
+        Credentials(CredentialsError),
+        HttpDispatch(HttpDispatchError),
+    }
+
+    impl From<CredentialsError> for TestError {
+        fn from(err: CredentialsError) -> TestError {
+            TestError::Credentials(err)
+        }
+    }
+
+    impl From<HttpDispatchError> for TestError {
+        fn from(err: HttpDispatchError) -> TestError {
+            TestError::HttpDispatch(err)
+        }
+    }
+
+    /// Echoes the request payload back along with the authorization header.
+    struct EchoDispatcher {
+        requests: Mutex<usize>,
+    }
+
+    impl DispatchSignedRequest for EchoDispatcher {
+        fn dispatch(&self, request: &SignedRequest) -> Result<HttpResponse, HttpDispatchError> {
+            *self.requests.lock().unwrap() += 1;
+
+            let mut response = HttpResponse::default();
+            response.status = 200;
+            response.body = request.payload.unwrap_or(&[]).to_vec();
+            if let Some(authorization) = request.headers.get("authorization") {
+                response.headers.insert("authorization".to_owned(), String::from_utf8_lossy(&authorization[0]).into_owned());
+            }
+            Ok(response)
+        }
+    }
+
+    #[test]
+    fn dispatch_on_thread_pool() {
+        let dispatcher = ThreadPoolDispatcher::new(EchoDispatcher { requests: Mutex::new(0) }, 2);
+
+        let payload = b"{\"TableName\": \"test\"}".to_vec();
+        let mut request = SignedRequest::new("POST", "dynamodb", Region::UsEast1, "/");
+        request.set_payload(Some(&payload[..]));
+
+        let future = sign_and_dispatch_async::<_, _, TestError>(&MockCredentialsProvider, &dispatcher, &Arc::new(RetryPolicy::default()),
+                                                                &InterceptorChain::new(), &mut request);
+        drop(request);
+        drop(payload);
+
+        let response = future.wait().unwrap();
+        assert_eq!(response.status, 200);
+        assert_eq!(response.body, b"{\"TableName\": \"test\"}".to_vec());
+        assert!(response.headers["authorization"].starts_with("AWS4-HMAC-SHA256 Credential=mock_key/"));
+        assert_eq!(*dispatcher.dispatcher.requests.lock().unwrap(), 1);
+    }
+
+    #[test]
+    fn async_requests_are_retried() {
+        let scripted = ScriptedDispatcher::new()
+            .respond_with(RequestMatcher::any(), 503, "")
+            .respond_with(RequestMatcher::any(), 200, "");
+        let dispatcher = ThreadPoolDispatcher::new(scripted.clone(), 1);
+        let policy = Arc::new(RetryPolicy::default().with_base_delay(Duration::from_millis(1)));
+        let mut request = SignedRequest::new("POST", "sqs", Region::UsEast1, "/");
+
+        let response = sign_and_dispatch_async::<_, _, TestError>(&MockCredentialsProvider, &dispatcher, &policy, &InterceptorChain::new(), &mut request)
+            .wait()
+            .unwrap();
+
+        assert_eq!(response.status, 200);
+        scripted.assert_calls(2);
+    }
+
+    #[test]
+    fn streamed_payloads_are_refused() {
+        let dispatcher = ThreadPoolDispatcher::new(EchoDispatcher { requests: Mutex::new(0) }, 1);
+
+        let mut request = SignedRequest::new("PUT", "s3", Region::UsEast1, "/bucket/key");
+        request.set_payload_stream(Cursor::new(vec![0; 16]), 16);
+
+        assert!(dispatcher.dispatch_async(&request).wait().is_err());
+        assert_eq!(*dispatcher.dispatcher.requests.lock().unwrap(), 0);
+    }
+}
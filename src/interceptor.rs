@@ -0,0 +1,257 @@
+//! Hooks around signing and dispatching requests.
+//!
+//! Interceptors are added to a client with `add_interceptor` and run for every request the
+//! client makes.  `before_signing` is called once per request; the other hooks are called for
+//! every attempt at it, retries included.
+
+use std::fmt;
+use std::sync::Arc;
+
+use credential::AwsCredentials;
+use request::{DispatchSignedRequest, HttpDispatchError, HttpResponse, HttpStreamingResponse};
+use signature::SignedRequest;
+
+/// Sees, and may change, requests and their responses.
+///
+/// Every hook has a default implementation that does nothing, so an interceptor only needs to
+/// implement the ones it is interested in.
+pub trait Interceptor: Send + Sync {
+    /// Called before the request is signed, so any headers or parameters added here are
+    /// covered by the signature.  It is called once per request, however often the request
+    /// is retried.
+    ///
+    /// Returning a result ends the request with it: the request isn't sent, and no later
+    /// hook of any interceptor is called.
+    fn before_signing(&self, _request: &mut SignedRequest) -> Option<Result<HttpResponse, HttpDispatchError>> {
+        None
+    }
+
+    /// Called once the request has been signed, just before it is sent, for every attempt.
+    /// Changes made here are not covered by the signature, and are still in place when a
+    /// retry is signed, so headers should be removed before they are added again.
+    ///
+    /// Returning a result ends the attempt with it, without sending the request.
+    fn after_signing(&self, _request: &mut SignedRequest) -> Option<Result<HttpResponse, HttpDispatchError>> {
+        None
+    }
+
+    /// Called with the outcome of sending the request, which may be replaced.
+    fn after_response(&self, _request: &SignedRequest, _result: &mut Result<HttpResponse, HttpDispatchError>) {}
+
+    /// Like `after_response`, for responses whose body is read on demand, as returned by
+    /// `S3Client::get_object_streaming`.
+    fn after_streaming_response(&self, _request: &SignedRequest, _result: &mut Result<HttpStreamingResponse, HttpDispatchError>) {}
+}
+
+/// The interceptors of a client, in the order they were added.
+///
+/// The hooks called before a request is sent run in that order, and those called with its
+/// response run in reverse order, so the first interceptor added sees the request first and
+/// the response last.
+#[derive(Clone, Default)]
+pub struct InterceptorChain {
+    interceptors: Vec<Arc<Interceptor>>,
+}
+
+impl fmt::Debug for InterceptorChain {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "InterceptorChain {{ {} interceptors }}", self.interceptors.len())
+    }
+}
+
+impl InterceptorChain {
+    pub fn new() -> InterceptorChain {
+        InterceptorChain::default()
+    }
+
+    /// Add `interceptor` to the end of the chain.
+    pub fn push<I>(&mut self, interceptor: I) where I: Interceptor + 'static {
+        self.interceptors.push(Arc::new(interceptor));
+    }
+
+    pub fn len(&self) -> usize {
+        self.interceptors.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.interceptors.is_empty()
+    }
+
+    /// Run the `before_signing` hooks, once per request.
+    ///
+    /// Returns the result an interceptor ended the request with, if any.
+    pub fn before_signing(&self, request: &mut SignedRequest) -> Option<Result<HttpResponse, HttpDispatchError>> {
+        for interceptor in &self.interceptors {
+            if let Some(result) = interceptor.before_signing(request) {
+                return Some(result);
+            }
+        }
+
+        None
+    }
+
+    /// Sign the request and run the `after_signing` hooks, once per attempt.
+    ///
+    /// Returns the result an interceptor ended the attempt with, if any.
+    pub fn sign(&self, request: &mut SignedRequest, creds: &AwsCredentials) -> Option<Result<HttpResponse, HttpDispatchError>> {
+        request.sign(creds);
+
+        for interceptor in &self.interceptors {
+            if let Some(result) = interceptor.after_signing(request) {
+                return Some(result);
+            }
+        }
+
+        None
+    }
+
+    /// Run the `after_response` hooks on the outcome of sending `request`.
+    pub fn after_response(&self, request: &SignedRequest, result: &mut Result<HttpResponse, HttpDispatchError>) {
+        for interceptor in self.interceptors.iter().rev() {
+            interceptor.after_response(request, result);
+        }
+    }
+
+    /// Sign `request` and send it once, calling every hook along the way.
+    pub fn sign_and_dispatch<D>(&self, dispatcher: &D, request: &mut SignedRequest, creds: &AwsCredentials)
+        -> Result<HttpResponse, HttpDispatchError> where D: DispatchSignedRequest
+    {
+        if let Some(result) = self.before_signing(request) {
+            return result;
+        }
+
+        self.send_attempt(dispatcher, request, creds)
+    }
+
+    /// Sign `request` and send it, calling the hooks run for every attempt but not
+    /// `before_signing`.
+    pub fn send_attempt<D>(&self, dispatcher: &D, request: &mut SignedRequest, creds: &AwsCredentials)
+        -> Result<HttpResponse, HttpDispatchError> where D: DispatchSignedRequest
+    {
+        if let Some(result) = self.sign(request, creds) {
+            return result;
+        }
+
+        let mut result = dispatcher.dispatch(request);
+        self.after_response(request, &mut result);
+        result
+    }
+
+    /// Like `sign_and_dispatch`, but leaves the body of the response unread.
+    pub fn sign_and_dispatch_streaming<D>(&self, dispatcher: &D, request: &mut SignedRequest, creds: &AwsCredentials)
+        -> Result<HttpStreamingResponse, HttpDispatchError> where D: DispatchSignedRequest
+    {
+        if let Some(result) = self.before_signing(request) {
+            return result.map(HttpStreamingResponse::from);
+        }
+
+        self.send_attempt_streaming(dispatcher, request, creds)
+    }
+
+    /// Like `send_attempt`, but leaves the body of the response unread.
+    pub fn send_attempt_streaming<D>(&self, dispatcher: &D, request: &mut SignedRequest, creds: &AwsCredentials)
+        -> Result<HttpStreamingResponse, HttpDispatchError> where D: DispatchSignedRequest
+    {
+        if let Some(result) = self.sign(request, creds) {
+            return result.map(HttpStreamingResponse::from);
+        }
+
+        let mut result = dispatcher.dispatch_streaming(request);
+        for interceptor in self.interceptors.iter().rev() {
+            interceptor.after_streaming_response(request, &mut result);
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use chrono::{Duration, UTC};
+
+    use credential::AwsCredentials;
+    use mock::MockRequestDispatcher;
+    use region::Region;
+    use request::{HttpDispatchError, HttpResponse};
+    use signature::SignedRequest;
+    use super::*;
+
+    struct Recorder {
+        name: &'static str,
+        log: Arc<Mutex<Vec<String>>>,
+    }
+
+    impl Interceptor for Recorder {
+        fn before_signing(&self, request: &mut SignedRequest) -> Option<Result<HttpResponse, HttpDispatchError>> {
+            self.log.lock().unwrap().push(format!("{} before signing", self.name));
+            request.add_header("x-trace-id", "abc123");
+            None
+        }
+
+        fn after_signing(&self, request: &mut SignedRequest) -> Option<Result<HttpResponse, HttpDispatchError>> {
+            let signed = request.headers().contains_key("authorization");
+            self.log.lock().unwrap().push(format!("{} after signing, signed: {}", self.name, signed));
+            None
+        }
+
+        fn after_response(&self, _request: &SignedRequest, result: &mut Result<HttpResponse, HttpDispatchError>) {
+            let status = result.as_ref().map(|response| response.status).unwrap_or(0);
+            self.log.lock().unwrap().push(format!("{} after response {}", self.name, status));
+        }
+    }
+
+    struct ShortCircuit;
+
+    impl Interceptor for ShortCircuit {
+        fn before_signing(&self, _request: &mut SignedRequest) -> Option<Result<HttpResponse, HttpDispatchError>> {
+            let mut response = HttpResponse::default();
+            response.status = 204;
+            Some(Ok(response))
+        }
+    }
+
+    fn credentials() -> AwsCredentials {
+        AwsCredentials::new("key", "secret", None, UTC::now() + Duration::seconds(600))
+    }
+
+    #[test]
+    fn hooks_run_in_order() {
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let mut chain = InterceptorChain::new();
+        chain.push(Recorder { name: "first", log: log.clone() });
+        chain.push(Recorder { name: "second", log: log.clone() });
+
+        let dispatcher = MockRequestDispatcher::with_status(200).with_request_checker(|request: &SignedRequest| {
+            let signed_headers = String::from_utf8_lossy(&request.headers()["authorization"][0]).into_owned();
+            assert!(signed_headers.contains("x-trace-id"));
+        });
+        let mut request = SignedRequest::new("GET", "sqs", Region::UsEast1, "/");
+
+        let response = chain.sign_and_dispatch(&dispatcher, &mut request, &credentials()).unwrap();
+        assert_eq!(response.status, 200);
+        assert_eq!(*log.lock().unwrap(), vec![
+            "first before signing",
+            "second before signing",
+            "first after signing, signed: true",
+            "second after signing, signed: true",
+            "second after response 200",
+            "first after response 200",
+        ]);
+    }
+
+    #[test]
+    fn short_circuit_skips_dispatch() {
+        let mut chain = InterceptorChain::new();
+        chain.push(ShortCircuit);
+
+        let dispatcher = MockRequestDispatcher::with_status(200).with_request_checker(|_: &SignedRequest| {
+            panic!("the request should not have been sent");
+        });
+        let mut request = SignedRequest::new("GET", "sqs", Region::UsEast1, "/");
+
+        let response = chain.sign_and_dispatch(&dispatcher, &mut request, &credentials()).unwrap();
+        assert_eq!(response.status, 204);
+        assert!(!request.headers().contains_key("authorization"));
+    }
+}
@@ -44,16 +44,23 @@
 //! }
 
 extern crate chrono;
+#[cfg(feature = "async")] extern crate curl;
+#[cfg(feature = "async")] extern crate futures;
+#[cfg(feature = "async")] extern crate futures_cpupool;
 extern crate hyper;
 #[macro_use] extern crate lazy_static;
 #[macro_use] extern crate log;
 extern crate md5;
+extern crate rand;
 extern crate regex;
 extern crate ring;
 extern crate rustc_serialize;
 extern crate serde;
 extern crate serde_json;
 extern crate time;
+#[cfg(feature = "async")] extern crate tokio_core;
+#[cfg(feature = "async")] extern crate tokio_curl;
+#[cfg(feature = "async")] extern crate tokio_timer;
 extern crate url;
 extern crate xml;
 
@@ -68,21 +75,62 @@ pub use credential::{
     DefaultCredentialsProvider,
     DefaultCredentialsProviderSync,
 };
+#[cfg(feature = "async")]
+pub use curl_dispatcher::CurlDispatcher;
+pub use endpoint::{Endpoint, ParseEndpointError};
+pub use endpoint_resolver::{ResolvedEndpoint, resolve_endpoint, resolve_global_endpoint};
+pub use error::{AwsError, UnknownError};
+pub use fault::{Fault, FaultInjectingDispatcher};
+#[cfg(feature = "async")]
+pub use future::{DispatchSignedRequestAsync, RusotoFuture, ThreadPoolDispatcher, sign_and_dispatch_async};
+pub use connector::{Connector, DeadlineStream, ProxiedStream};
+pub use interceptor::{Interceptor, InterceptorChain};
+pub use metadata::ResponseMetadata;
+pub use post_policy::{PostPolicy, PostPolicyForm};
+pub use proxy::{ProxyConfig, ProxyScope};
 pub use region::{ParseRegionError, Region};
-pub use request::{DispatchSignedRequest, HttpResponse, HttpDispatchError};
-pub use signature::SignedRequest;
+pub use region_provider::{
+    ChainRegionProvider,
+    EnvironmentRegionProvider,
+    InstanceMetadataRegionProvider,
+    ProfileRegionProvider,
+    ProvideRegion,
+    RegionError,
+};
+pub use request::{default_client, DispatchSignedRequest, HttpConfig, HttpResponse, HttpStreamingResponse, HttpDispatchError, HttpDispatchErrorKind};
+pub use replay::{MatchOn, RecordingDispatcher, ReplayDispatcher};
+pub use retry::{RetryPolicy, is_retryable_response};
+pub use signature::{SignedRequest, PayloadSigning, PayloadStream, ChunkedPayloadReader};
+pub use verification::{SignatureVerifier, VerificationError, VerifiedSignature};
 
+mod connector;
 mod credential;
+#[cfg(feature = "async")]
+mod curl_dispatcher;
+mod endpoint;
+mod endpoint_resolver;
+mod error;
+mod fault;
+#[cfg(feature = "async")]
+mod future;
+mod interceptor;
+mod metadata;
 mod param;
+mod post_policy;
+mod proxy;
 mod region;
+mod region_provider;
+mod replay;
 mod request;
+mod retry;
 mod xmlerror;
 mod xmlutil;
 mod serialization;
 #[macro_use] mod signature;
+mod verification;
 
-#[cfg(test)]
-mod mock;
+#[cfg(any(test, feature = "mock"))]
+pub mod mock;
 
 #[cfg(feature = "acm")]
 pub mod acm;
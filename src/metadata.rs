@@ -0,0 +1,163 @@
+//! Metadata about the response to a request, such as the ID AWS gave the request.
+
+use std::ascii::AsciiExt;
+use std::collections::HashMap;
+
+use request::{HttpResponse, HttpStreamingResponse};
+
+/// Headers carrying the request ID, in order of preference.
+const REQUEST_ID_HEADERS: &'static [&'static str] = &["x-amzn-RequestId", "x-amz-request-id"];
+
+/// Headers carrying the extended request ID.
+const EXTENDED_REQUEST_ID_HEADERS: &'static [&'static str] = &["x-amz-id-2"];
+
+/// Elements of XML bodies carrying the request ID.  EC2 spells it two different ways.
+const REQUEST_ID_ELEMENTS: &'static [&'static str] = &["RequestId", "requestId", "RequestID"];
+
+/// Elements of XML bodies carrying the extended request ID, used by S3 errors.
+const EXTENDED_REQUEST_ID_ELEMENTS: &'static [&'static str] = &["HostId"];
+
+/// What AWS said about a response besides its body.
+///
+/// AWS support will want the request ID, and for S3 the extended request ID, of any request
+/// you ask them about.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ResponseMetadata {
+    /// The ID AWS gave the request.
+    pub request_id: Option<String>,
+    /// The extended request ID S3 sends as `x-amz-id-2`.
+    pub extended_request_id: Option<String>,
+    /// The HTTP status of the response.
+    pub status: u16,
+    /// Every header of the response.
+    pub headers: HashMap<String, String>,
+}
+
+impl ResponseMetadata {
+    /// Gather metadata from the status and headers of a response.  When an error response has
+    /// no request ID header, the request IDs are looked for in its body instead.
+    ///
+    /// The bodies of successful responses are never searched: they may be large, and may hold
+    /// user data that only looks like a request ID.
+    pub fn from_response(response: &HttpResponse) -> ResponseMetadata {
+        let mut metadata = ResponseMetadata::from_headers(response.status, &response.headers);
+
+        if response.status >= 400 && metadata.request_id.is_none() {
+            let body = String::from_utf8_lossy(&response.body);
+            metadata.request_id = find_element(&body, REQUEST_ID_ELEMENTS);
+            if metadata.extended_request_id.is_none() {
+                metadata.extended_request_id = find_element(&body, EXTENDED_REQUEST_ID_ELEMENTS);
+            }
+        }
+
+        metadata
+    }
+
+    /// Like `from_response`, for responses whose body hasn't been read, which is left alone.
+    pub fn from_streaming_response(response: &HttpStreamingResponse) -> ResponseMetadata {
+        ResponseMetadata::from_headers(response.status, &response.headers)
+    }
+
+    fn from_headers(status: u16, headers: &HashMap<String, String>) -> ResponseMetadata {
+        ResponseMetadata {
+            request_id: find_header(headers, REQUEST_ID_HEADERS),
+            extended_request_id: find_header(headers, EXTENDED_REQUEST_ID_HEADERS),
+            status: status,
+            headers: headers.clone(),
+        }
+    }
+
+    /// The value of the header `name`, compared case-insensitively.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers.iter()
+            .find(|&(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| &value[..])
+    }
+}
+
+fn find_header(headers: &HashMap<String, String>, names: &[&str]) -> Option<String> {
+    names.iter()
+        .filter_map(|name| headers.iter().find(|&(key, _)| key.eq_ignore_ascii_case(name)))
+        .map(|(_, value)| value.trim().to_owned())
+        .find(|value| !value.is_empty())
+}
+
+/// The text of the first of the elements `names` found in the XML `body`.
+///
+/// This is a plain text search rather than a parse, since it only has to find a leaf element
+/// and is run on error bodies that may not be well formed.
+fn find_element(body: &str, names: &[&str]) -> Option<String> {
+    names.iter().filter_map(|name| {
+        let open = format!("<{}>", name);
+        let close = format!("</{}>", name);
+
+        body.find(&open[..]).and_then(|start| {
+            let text = &body[start + open.len()..];
+            text.find(&close[..]).map(|end| text[..end].trim().to_owned())
+        })
+    }).find(|value| !value.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use request::HttpResponse;
+    use super::*;
+
+    fn response(status: u16, headers: &[(&str, &str)], body: &str) -> HttpResponse {
+        let mut response = HttpResponse::default();
+        response.status = status;
+        response.body = body.as_bytes().to_vec();
+        for &(name, value) in headers {
+            response.headers.insert(name.to_owned(), value.to_owned());
+        }
+        response
+    }
+
+    #[test]
+    fn request_ids_from_headers() {
+        let metadata = ResponseMetadata::from_response(&response(200, &[
+            ("X-Amzn-RequestId", "b0a2c6a2-7f3e-11e6-a8a4-35b1b0dba4a1"),
+            ("Content-Type", "application/x-amz-json-1.0"),
+        ], "{}"));
+        assert_eq!(metadata.request_id, Some("b0a2c6a2-7f3e-11e6-a8a4-35b1b0dba4a1".to_owned()));
+        assert_eq!(metadata.extended_request_id, None);
+        assert_eq!(metadata.status, 200);
+        assert_eq!(metadata.header("content-type"), Some("application/x-amz-json-1.0"));
+
+        let metadata = ResponseMetadata::from_response(&response(404, &[
+            ("x-amz-request-id", "4442587FB7D0A2F9"),
+            ("x-amz-id-2", "ZbO8Ip9S2OG6yGXx1vNBXvWXO0Dk4kGiN9+pQm0YtfIg="),
+        ], ""));
+        assert_eq!(metadata.request_id, Some("4442587FB7D0A2F9".to_owned()));
+        assert_eq!(metadata.extended_request_id, Some("ZbO8Ip9S2OG6yGXx1vNBXvWXO0Dk4kGiN9+pQm0YtfIg=".to_owned()));
+    }
+
+    #[test]
+    fn request_ids_from_xml_bodies() {
+        let metadata = ResponseMetadata::from_response(&response(400, &[],
+            "<ErrorResponse><Error><Code>Throttling</Code></Error><RequestId>42d59b56-7407-4c4a-be0f-4c88daeea257</RequestId></ErrorResponse>"));
+        assert_eq!(metadata.request_id, Some("42d59b56-7407-4c4a-be0f-4c88daeea257".to_owned()));
+
+        let metadata = ResponseMetadata::from_response(&response(400, &[],
+            "<Response><Errors><Error><Code>InvalidInstanceID.NotFound</Code></Error></Errors><RequestID>ea966190-f9aa-478e-9ede-cb5432daacc0</RequestID></Response>"));
+        assert_eq!(metadata.request_id, Some("ea966190-f9aa-478e-9ede-cb5432daacc0".to_owned()));
+
+        let metadata = ResponseMetadata::from_response(&response(403, &[],
+            "<Error><Code>AccessDenied</Code><RequestId>4442587FB7D0A2F9</RequestId><HostId>uV2EVE0+2Q6r8U4yZdOd</HostId></Error>"));
+        assert_eq!(metadata.request_id, Some("4442587FB7D0A2F9".to_owned()));
+        assert_eq!(metadata.extended_request_id, Some("uV2EVE0+2Q6r8U4yZdOd".to_owned()));
+    }
+
+    #[test]
+    fn bodies_are_only_searched_when_needed() {
+        let metadata = ResponseMetadata::from_response(&response(200, &[],
+            "<GetObjectResult><Body>&lt;RequestId&gt;<RequestId>user data</RequestId></Body></GetObjectResult>"));
+        assert_eq!(metadata.request_id, None);
+        assert_eq!(metadata.extended_request_id, None);
+
+        let metadata = ResponseMetadata::from_response(&response(403, &[("x-amz-request-id", "4442587FB7D0A2F9")],
+            "<Error><Code>AccessDenied</Code><RequestId>ignored</RequestId><HostId>ignored</HostId></Error>"));
+        assert_eq!(metadata.request_id, Some("4442587FB7D0A2F9".to_owned()));
+        assert_eq!(metadata.extended_request_id, None);
+    }
+}
@@ -1,7 +1,11 @@
-//! Mock request dispatcher and credentials for unit testing services
+//! Mock request dispatchers and credentials for unit testing services
+//!
+//! Available to other crates with the `mock` feature.
 
+use std::collections::VecDeque;
 use std::fs::File;
 use std::io::Read;
+use std::sync::{Arc, Mutex};
 
 use super::{DispatchSignedRequest, HttpResponse, HttpDispatchError, SignedRequest};
 use super::{ProvideAwsCredentials, CredentialsError, AwsCredentials};
@@ -33,7 +37,7 @@ impl MockRequestDispatcher {
 	}
 
 	pub fn with_body(mut self, body: &str) -> MockRequestDispatcher {
-		self.mock_response.body = body.to_owned();
+		self.mock_response.body = body.as_bytes().to_vec();
 		self
 	}
 
@@ -58,6 +62,138 @@ impl DispatchSignedRequest for MockRequestDispatcher {
 	}
 }
 
+/// Which requests a step of a `ScriptedDispatcher` expects.  Parts that aren't set match
+/// anything.
+#[derive(Clone, Debug, Default)]
+pub struct RequestMatcher {
+	method: Option<String>,
+	path: Option<String>,
+	target: Option<String>,
+	params: Vec<(String, String)>,
+}
+
+impl RequestMatcher {
+	/// Matches every request.
+	pub fn any() -> RequestMatcher {
+		RequestMatcher::default()
+	}
+
+	pub fn with_method(mut self, method: &str) -> RequestMatcher {
+		self.method = Some(method.to_owned());
+		self
+	}
+
+	pub fn with_path(mut self, path: &str) -> RequestMatcher {
+		self.path = Some(path.to_owned());
+		self
+	}
+
+	/// Match the `x-amz-target` header of JSON protocol services, such as
+	/// `DynamoDB_20120810.ListTables`.
+	pub fn with_target(mut self, target: &str) -> RequestMatcher {
+		self.target = Some(target.to_owned());
+		self
+	}
+
+	/// Match a parameter, such as `Action` for query protocol services.
+	pub fn with_param(mut self, key: &str, value: &str) -> RequestMatcher {
+		self.params.push((key.to_owned(), value.to_owned()));
+		self
+	}
+
+	pub fn matches(&self, request: &SignedRequest) -> bool {
+		let target = request.headers().get("x-amz-target")
+			.and_then(|values| values.first())
+			.map(|value| String::from_utf8_lossy(value).into_owned());
+
+		self.method.as_ref().map_or(true, |method| method == request.method()) &&
+			self.path.as_ref().map_or(true, |path| path == request.path()) &&
+			self.target.as_ref().map_or(true, |expected| Some(expected) == target.as_ref()) &&
+			self.params.iter().all(|&(ref key, ref value)| request.params.get(key) == Some(value))
+	}
+}
+
+/// Answers requests from a script: a queue of expected requests and the response, or error,
+/// to give each of them.
+///
+/// A request that doesn't match the next step, or that arrives once the script is used up,
+/// panics so the test fails.  Clones share the script, so a test can keep one to check the
+/// calls made through another given to a client.
+#[derive(Clone)]
+pub struct ScriptedDispatcher {
+	steps: Arc<Mutex<VecDeque<(RequestMatcher, Result<HttpResponse, HttpDispatchError>)>>>,
+	calls: Arc<Mutex<usize>>,
+}
+
+impl ScriptedDispatcher {
+	pub fn new() -> ScriptedDispatcher {
+		ScriptedDispatcher {
+			steps: Arc::new(Mutex::new(VecDeque::new())),
+			calls: Arc::new(Mutex::new(0)),
+		}
+	}
+
+	/// Expect a request matching `matcher` and answer it with `response`.
+	pub fn respond(self, matcher: RequestMatcher, response: HttpResponse) -> ScriptedDispatcher {
+		self.steps.lock().unwrap().push_back((matcher, Ok(response)));
+		self
+	}
+
+	/// Expect a request matching `matcher` and answer it with a response with `status` and
+	/// `body`.
+	pub fn respond_with(self, matcher: RequestMatcher, status: u16, body: &str) -> ScriptedDispatcher {
+		let mut response = HttpResponse::default();
+		response.status = status;
+		response.body = body.as_bytes().to_vec();
+		self.respond(matcher, response)
+	}
+
+	/// Expect a request matching `matcher` and fail it with `error`, as if it couldn't be sent.
+	pub fn fail(self, matcher: RequestMatcher, error: HttpDispatchError) -> ScriptedDispatcher {
+		self.steps.lock().unwrap().push_back((matcher, Err(error)));
+		self
+	}
+
+	/// The number of requests dispatched so far.
+	pub fn calls(&self) -> usize {
+		*self.calls.lock().unwrap()
+	}
+
+	/// The number of steps left in the script.
+	pub fn remaining(&self) -> usize {
+		self.steps.lock().unwrap().len()
+	}
+
+	/// Panic unless exactly `expected` requests were dispatched.
+	pub fn assert_calls(&self, expected: usize) {
+		assert_eq!(self.calls(), expected, "expected {} requests, but {} were dispatched", expected, self.calls());
+	}
+
+	/// Panic unless every step of the script was used.
+	pub fn assert_done(&self) {
+		assert_eq!(self.remaining(), 0, "{} scripted responses were never requested", self.remaining());
+	}
+}
+
+impl DispatchSignedRequest for ScriptedDispatcher {
+	fn dispatch(&self, request: &SignedRequest) -> Result<HttpResponse, HttpDispatchError> {
+		*self.calls.lock().unwrap() += 1;
+
+		// release the lock before any panic, so the dispatcher can still be inspected
+		let step = self.steps.lock().unwrap().pop_front();
+		let (matcher, result) = match step {
+			Some(step) => step,
+			None => panic!("unexpected request {} {}: the script is used up", request.method(), request.path()),
+		};
+
+		if !matcher.matches(request) {
+			panic!("request {} {} does not match the next step of the script, {:?}", request.method(), request.path(), matcher);
+		}
+
+		result
+	}
+}
+
 pub trait ReadMockResponse {
 	fn read_response(file_name: &str) -> String;
 }
@@ -81,3 +217,57 @@ impl ReadMockResponse for MockResponseReader {
         mock_response
 	} 
 }
+
+#[cfg(test)]
+mod tests {
+	use std::panic::{self, AssertUnwindSafe};
+
+	use request::{DispatchSignedRequest, HttpDispatchError, HttpDispatchErrorKind};
+	use region::Region;
+	use signature::SignedRequest;
+	use super::*;
+
+	#[test]
+	fn scripted_responses() {
+		let dispatcher = ScriptedDispatcher::new()
+			.respond_with(RequestMatcher::any().with_target("DynamoDB_20120810.ListTables"), 200, "{}")
+			.fail(RequestMatcher::any().with_method("POST").with_param("Action", "SendMessage"),
+				HttpDispatchError::new(HttpDispatchErrorKind::Timeout, "timed out"));
+
+		let mut request = SignedRequest::new("POST", "dynamodb", Region::UsEast1, "/");
+		request.add_header("x-amz-target", "DynamoDB_20120810.ListTables");
+		assert_eq!(dispatcher.clone().dispatch(&request).unwrap().body, b"{}".to_vec());
+
+		let mut request = SignedRequest::new("POST", "sqs", Region::UsEast1, "/");
+		request.params.insert("Action".to_owned(), "SendMessage".to_owned());
+		assert!(dispatcher.dispatch(&request).unwrap_err().is_timeout());
+
+		dispatcher.assert_calls(2);
+		dispatcher.assert_done();
+	}
+
+	#[test]
+	#[should_panic(expected = "does not match")]
+	fn unexpected_request() {
+		let dispatcher = ScriptedDispatcher::new().respond_with(RequestMatcher::any().with_path("/queue"), 200, "");
+		let _ = dispatcher.dispatch(&SignedRequest::new("GET", "sqs", Region::UsEast1, "/"));
+	}
+
+	#[test]
+	fn panics_leave_the_script_usable() {
+		let dispatcher = ScriptedDispatcher::new().respond_with(RequestMatcher::any().with_path("/queue"), 200, "");
+
+		let mismatch = panic::catch_unwind(AssertUnwindSafe(|| {
+			let _ = dispatcher.dispatch(&SignedRequest::new("GET", "sqs", Region::UsEast1, "/"));
+		}));
+		assert!(mismatch.is_err());
+		assert_eq!(dispatcher.remaining(), 0);
+
+		let used_up = panic::catch_unwind(AssertUnwindSafe(|| {
+			let _ = dispatcher.dispatch(&SignedRequest::new("GET", "sqs", Region::UsEast1, "/queue"));
+		}));
+		assert!(used_up.is_err());
+		dispatcher.assert_calls(2);
+		dispatcher.assert_done();
+	}
+}
@@ -8,9 +8,11 @@ use std::error::Error;
 use std::str::FromStr;
 use std::fmt::{Display, Error as FmtError, Formatter};
 
+use endpoint::{Endpoint, ParseEndpointError};
+
 /// An AWS region.
 /// `CnNorth1` is currently untested due to Rusoto maintainers not having access to AWS China.
-#[derive(Copy, Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq)]
 pub enum Region {
     ApNortheast1,
     ApNortheast2,
@@ -24,6 +26,16 @@ pub enum Region {
     UsWest1,
     UsWest2,
     CnNorth1,
+    /// A region this release doesn't list, such as a newly launched one, or a local stand-in
+    /// for AWS.  Requests are signed for `name` and sent to `endpoint`, such as
+    /// `http://localhost:8000`, if one is given, else to the default endpoint for `name`.
+    ///
+    /// Build it with `Region::custom` to check the endpoint up front; requests for a region
+    /// whose endpoint isn't a valid URL fail.
+    Custom {
+        name: String,
+        endpoint: Option<String>,
+    },
 }
 
 /// An error produced when attempting to convert a `str` into a `Region` fails.
@@ -32,9 +44,25 @@ pub struct ParseRegionError {
     message: String,
 }
 
-impl Display for Region {
-    fn fmt(&self, f: &mut Formatter) -> Result<(), FmtError> {
-        let region_str = match *self {
+impl Region {
+    /// A `Region::Custom`, checking that `endpoint`, if one is given, is a valid URL such as
+    /// `http://localhost:8000`.
+    pub fn custom<S>(name: S, endpoint: Option<&str>) -> Result<Region, ParseRegionError> where S: Into<String> {
+        if let Some(endpoint) = endpoint {
+            if let Err(err) = endpoint.parse::<Endpoint>() {
+                return Err(ParseRegionError { message: err.to_string() });
+            }
+        }
+
+        Ok(Region::Custom {
+            name: name.into(),
+            endpoint: endpoint.map(|endpoint| endpoint.to_owned()),
+        })
+    }
+
+    /// The name AWS gives the region, such as `us-east-1`.
+    pub fn name(&self) -> &str {
+        match *self {
             Region::ApNortheast1 => "ap-northeast-1",
             Region::ApNortheast2 => "ap-northeast-2",
             Region::ApSouth1 => "ap-south-1",
@@ -47,15 +75,35 @@ impl Display for Region {
             Region::UsWest1 => "us-west-1",
             Region::UsWest2 => "us-west-2",
             Region::CnNorth1 => "cn-north-1",
-        };
+            Region::Custom { ref name, .. } => &name[..],
+        }
+    }
+
+    /// The endpoint of a custom region, if it was given one that is a valid URL.
+    pub fn endpoint(&self) -> Option<Endpoint> {
+        self.custom_endpoint().unwrap_or(None)
+    }
+
+    /// The endpoint of a custom region, if it was given one, or the error parsing it.
+    pub fn custom_endpoint(&self) -> Result<Option<Endpoint>, ParseEndpointError> {
+        match *self {
+            Region::Custom { endpoint: Some(ref endpoint), .. } => endpoint.parse().map(Some),
+            _ => Ok(None),
+        }
+    }
+}
 
-        write!(f, "{}", region_str)
+impl Display for Region {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), FmtError> {
+        write!(f, "{}", self.name())
     }
 }
 
 impl FromStr for Region {
     type Err = ParseRegionError;
 
+    /// Parse the name of a region.  Names this release doesn't list, but that look like a
+    /// region's, such as `ca-central-1`, give a `Region::Custom`.
     fn from_str(s: &str) -> Result<Region, ParseRegionError> {
         match s {
             "ap-northeast-1" => Ok(Region::ApNortheast1),
@@ -70,11 +118,21 @@ impl FromStr for Region {
             "us-west-1" => Ok(Region::UsWest1),
             "us-west-2" => Ok(Region::UsWest2),
             "cn-north-1" => Ok(Region::CnNorth1),
+            s if is_region_name(s) => Ok(Region::Custom { name: s.to_owned(), endpoint: None }),
             s => Err(ParseRegionError::new(s))
         }
     }
 }
 
+/// Whether `s` is made of words of lowercase letters and digits joined by dashes, as region
+/// names are.
+fn is_region_name(s: &str) -> bool {
+    s.contains('-') &&
+        s.split('-').all(|word| {
+            !word.is_empty() && word.chars().all(|c| (c >= 'a' && c <= 'z') || (c >= '0' && c <= '9'))
+        })
+}
+
 impl ParseRegionError {
     pub fn new(input: &str) -> Self {
         ParseRegionError {
@@ -119,6 +177,31 @@ mod tests {
         assert_eq!("us-west-1".parse(), Ok(Region::UsWest1));
         assert_eq!("us-west-2".parse(), Ok(Region::UsWest2));
         assert_eq!("cn-north-1".parse(), Ok(Region::CnNorth1));
+        assert_eq!("ca-central-1".parse(), Ok(Region::Custom { name: "ca-central-1".to_owned(), endpoint: None }));
+        assert!("-central-1".parse::<Region>().is_err());
+        assert!("US-EAST-1".parse::<Region>().is_err());
+    }
+
+    #[test]
+    fn custom_regions() {
+        let region = Region::Custom { name: "local".to_owned(), endpoint: Some("http://localhost:8000".to_owned()) };
+        assert_eq!(region.to_string(), "local");
+        assert_eq!(region.endpoint(), Some("http://localhost:8000".parse().unwrap()));
+
+        let region = Region::Custom { name: "local".to_owned(), endpoint: Some("localhost:8000".to_owned()) };
+        assert_eq!(region.endpoint(), None);
+        assert!(region.custom_endpoint().is_err());
+        assert_eq!(Region::UsEast1.endpoint(), None);
+        assert_eq!(Region::UsEast1.custom_endpoint(), Ok(None));
+    }
+
+    #[test]
+    fn custom_constructor_checks_the_endpoint() {
+        assert_eq!(Region::custom("local", Some("http://localhost:8000")),
+                   Ok(Region::Custom { name: "local".to_owned(), endpoint: Some("http://localhost:8000".to_owned()) }));
+        assert_eq!(Region::custom("us-north-9", None), Ok(Region::Custom { name: "us-north-9".to_owned(), endpoint: None }));
+        assert_eq!(Region::custom("local", Some("localhost:8000")).unwrap_err().to_string(),
+                   "Not a valid endpoint URL: localhost:8000");
     }
 
     #[test]
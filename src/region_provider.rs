@@ -0,0 +1,355 @@
+//! Finding the region to use from the environment, the AWS config file or the EC2 instance
+//! metadata, the way the AWS CLI does.
+
+use std::env;
+use std::error::Error;
+use std::fmt;
+use std::fs::{self, File};
+use std::io::prelude::*;
+use std::io::BufReader;
+use std::io::Error as IoError;
+use std::path::{Path, PathBuf};
+use std::time::Duration as StdDuration;
+
+use hyper::header::Connection;
+use regex::Regex;
+
+use region::{ParseRegionError, Region};
+use request::HttpConfig;
+
+/// An error finding the region to use.
+#[derive(Debug, PartialEq)]
+pub struct RegionError {
+    pub message: String,
+}
+
+impl RegionError {
+    fn new(message: &str) -> RegionError {
+        RegionError {
+            message: message.to_owned(),
+        }
+    }
+}
+
+impl fmt::Display for RegionError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.description())
+    }
+}
+
+impl Error for RegionError {
+    fn description(&self) -> &str {
+        &self.message
+    }
+}
+
+impl From<ParseRegionError> for RegionError {
+    fn from(err: ParseRegionError) -> RegionError {
+        RegionError::new(err.description())
+    }
+}
+
+impl From<IoError> for RegionError {
+    fn from(err: IoError) -> RegionError {
+        RegionError::new(err.description())
+    }
+}
+
+/// A trait for types that find the `Region` to use.
+pub trait ProvideRegion {
+    /// Find the `Region` to use.
+    fn region(&self) -> Result<Region, RegionError>;
+}
+
+/// Provides the region from the `AWS_DEFAULT_REGION` environment variable, or failing that
+/// `AWS_REGION`.
+#[derive(Clone, Debug)]
+pub struct EnvironmentRegionProvider;
+
+impl ProvideRegion for EnvironmentRegionProvider {
+    fn region(&self) -> Result<Region, RegionError> {
+        match env::var("AWS_DEFAULT_REGION").or_else(|_| env::var("AWS_REGION")) {
+            Ok(name) => Ok(try!(name.trim().parse::<Region>())),
+            Err(_) => Err(RegionError::new("No AWS_DEFAULT_REGION or AWS_REGION in environment")),
+        }
+    }
+}
+
+/// Provides the region from the `region` key of a profile in an AWS config file.
+#[derive(Clone, Debug)]
+pub struct ProfileRegionProvider {
+    file_path: PathBuf,
+    profile: String,
+}
+
+impl ProfileRegionProvider {
+    /// Create a new `ProfileRegionProvider` for the config file named by `AWS_CONFIG_FILE`, or
+    /// the default path, and the profile named by `AWS_PROFILE`, or `default`.
+    pub fn new() -> Result<ProfileRegionProvider, RegionError> {
+        // Default config file location:
+        // ~/.aws/config (Linux/Mac)
+        // %USERPROFILE%\.aws\config  (Windows)
+        let config_location = match env::var("AWS_CONFIG_FILE") {
+            Ok(path) => PathBuf::from(path),
+            Err(_) => {
+                match env::home_dir() {
+                    Some(home_path) => home_path.join(".aws").join("config"),
+                    None => return Err(RegionError::new("The environment variable HOME must be set.")),
+                }
+            }
+        };
+
+        Ok(ProfileRegionProvider {
+            file_path: config_location,
+            profile: env::var("AWS_PROFILE").unwrap_or_else(|_| "default".to_owned()),
+        })
+    }
+
+    /// Create a new `ProfileRegionProvider` for the config file at the given path, using the
+    /// given profile.
+    pub fn with_configuration<F, P>(file_path: F, profile: P) -> ProfileRegionProvider
+    where F: Into<PathBuf>, P: Into<String> {
+        ProfileRegionProvider {
+            file_path: file_path.into(),
+            profile: profile.into(),
+        }
+    }
+
+    /// Get a reference to the config file path.
+    pub fn file_path(&self) -> &Path {
+        self.file_path.as_ref()
+    }
+
+    /// Get a reference to the profile name.
+    pub fn profile(&self) -> &str {
+        &self.profile
+    }
+
+    /// Set the config file path.
+    pub fn set_file_path<F>(&mut self, file_path: F) where F: Into<PathBuf> {
+        self.file_path = file_path.into();
+    }
+
+    /// Set the profile name.
+    pub fn set_profile<P>(&mut self, profile: P) where P: Into<String> {
+        self.profile = profile.into();
+    }
+}
+
+impl ProvideRegion for ProfileRegionProvider {
+    fn region(&self) -> Result<Region, RegionError> {
+        match try!(parse_config_file_region(self.file_path(), self.profile())) {
+            Some(name) => Ok(try!(name.parse::<Region>())),
+            None => Err(RegionError::new("No region in profile")),
+        }
+    }
+}
+
+/// Find the `region` key of `profile` in a config file.  Except for `default`, profiles are
+/// headed `[profile name]` in the config file.
+fn parse_config_file_region(file_path: &Path, profile: &str) -> Result<Option<String>, RegionError> {
+    match fs::metadata(file_path) {
+        Err(_) => return Err(RegionError::new("Couldn't stat config file.")),
+        Ok(metadata) => {
+            if !metadata.is_file() {
+                return Err(RegionError::new("Couldn't open file."));
+            }
+        }
+    };
+
+    let file = try!(File::open(file_path));
+
+    let section_regex = Regex::new(r"^\[\s*(?:profile\s+)?([^\]]+?)\s*\]$").unwrap();
+    let mut in_profile = false;
+
+    for line in BufReader::new(&file).lines() {
+        let line = try!(line);
+        let line = line.trim();
+
+        // skip comments
+        if line.starts_with('#') || line.starts_with(';') {
+            continue;
+        }
+
+        if let Some(caps) = section_regex.captures(line) {
+            in_profile = caps.at(1) == Some(profile);
+            continue;
+        }
+
+        if !in_profile {
+            continue;
+        }
+
+        let mut pair = line.splitn(2, '=');
+        if let (Some(key), Some(value)) = (pair.next(), pair.next()) {
+            if key.trim() == "region" && !value.trim().is_empty() {
+                return Ok(Some(value.trim().to_owned()));
+            }
+        }
+    }
+
+    Ok(None)
+}
+
+/// Provides the region the EC2 instance runs in, from the instance metadata service.
+#[derive(Clone, Debug)]
+pub struct InstanceMetadataRegionProvider;
+
+impl ProvideRegion for InstanceMetadataRegionProvider {
+    fn region(&self) -> Result<Region, RegionError> {
+        let address = "http://169.254.169.254/latest/meta-data/placement/region";
+        // the metadata service is only reachable on EC2, so fail fast everywhere else
+        let client = HttpConfig::default()
+            .with_connect_timeout(Some(StdDuration::from_secs(2)))
+            .with_read_timeout(Some(StdDuration::from_secs(15)))
+            .client();
+        let mut response = match client.get(address).header(Connection::close()).send() {
+            Err(_) => return Err(RegionError::new("Couldn't connect to metadata service")),
+            Ok(response) => response,
+        };
+
+        let mut body = String::new();
+        if let Err(_) = response.read_to_string(&mut body) {
+            return Err(RegionError::new("Didn't get a parsable response body from metadata service"));
+        }
+
+        region_from_metadata(&body)
+    }
+}
+
+/// The region named by the metadata service.  Availability zone names aren't trimmed down to
+/// a region, since those of local zones, such as `us-west-2-lax-1a`, don't end in the region's
+/// name.
+fn region_from_metadata(body: &str) -> Result<Region, RegionError> {
+    Ok(try!(body.trim().parse::<Region>()))
+}
+
+/// Provides the region from, in order:
+///
+/// 1. Environment variables: `AWS_DEFAULT_REGION` or `AWS_REGION`
+/// 2. AWS config file. Usually located at `~/.aws/config`.
+/// 3. EC2 instance metadata. Will only work if running on an EC2 instance.
+///
+/// If the sources are exhausted without finding a region, an error is returned.
+#[derive(Debug, Clone)]
+pub struct ChainRegionProvider {
+    environment_provider: Option<EnvironmentRegionProvider>,
+    profile_provider: Option<ProfileRegionProvider>,
+    instance_metadata_provider: Option<InstanceMetadataRegionProvider>,
+}
+
+impl ProvideRegion for ChainRegionProvider {
+    fn region(&self) -> Result<Region, RegionError> {
+        optional_region(&self.environment_provider)
+            .or_else(|_| optional_region(&self.profile_provider))
+            .or_else(|_| optional_region(&self.instance_metadata_provider))
+            .or_else(|_| Err(RegionError::new("Couldn't find an AWS region in environment, config file, or instance metadata.")))
+    }
+}
+
+/// The region from `provider`, if the chain has one.
+fn optional_region<P>(provider: &Option<P>) -> Result<Region, RegionError> where P: ProvideRegion {
+    match *provider {
+        Some(ref provider) => provider.region(),
+        None => Err(RegionError::new("")),
+    }
+}
+
+impl ChainRegionProvider {
+    /// Create a new `ChainRegionProvider` using a `ProfileRegionProvider` with the default
+    /// settings.
+    pub fn new() -> ChainRegionProvider {
+        ChainRegionProvider {
+            environment_provider: Some(EnvironmentRegionProvider),
+            profile_provider: ProfileRegionProvider::new().ok(),
+            instance_metadata_provider: Some(InstanceMetadataRegionProvider),
+        }
+    }
+
+    /// Create a new `ChainRegionProvider` using the provided `ProfileRegionProvider`.
+    pub fn with_profile_provider(profile_provider: ProfileRegionProvider) -> ChainRegionProvider {
+        ChainRegionProvider {
+            environment_provider: Some(EnvironmentRegionProvider),
+            profile_provider: Some(profile_provider),
+            instance_metadata_provider: Some(InstanceMetadataRegionProvider),
+        }
+    }
+
+    /// Set the provider for the environment variables, or skip them with `None`.
+    pub fn set_environment_provider(&mut self, environment_provider: Option<EnvironmentRegionProvider>) {
+        self.environment_provider = environment_provider;
+    }
+
+    /// Set the provider for the instance metadata, or skip it with `None`, as is worth doing
+    /// off EC2 to save waiting for the metadata service to time out.
+    pub fn set_instance_metadata_provider(&mut self, instance_metadata_provider: Option<InstanceMetadataRegionProvider>) {
+        self.instance_metadata_provider = instance_metadata_provider;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use region::Region;
+    use super::*;
+
+    #[test]
+    fn parse_config_file_profiles() {
+        let path = Path::new("tests/sample-data/multiple_profile_config");
+
+        assert_eq!(super::parse_config_file_region(path, "default"), Ok(Some("us-west-2".to_owned())));
+        assert_eq!(super::parse_config_file_region(path, "foo"), Ok(Some("eu-west-1".to_owned())));
+        assert_eq!(super::parse_config_file_region(path, "no_region"), Ok(None));
+        assert_eq!(super::parse_config_file_region(path, "not_a_profile"), Ok(None));
+    }
+
+    #[test]
+    fn parse_config_file_bad_path() {
+        let result = super::parse_config_file_region(Path::new("/bad/file/path"), "default");
+        assert_eq!(result, Err(RegionError::new("Couldn't stat config file.")));
+    }
+
+    #[test]
+    fn profile_region_provider() {
+        let mut provider = ProfileRegionProvider::with_configuration("tests/sample-data/multiple_profile_config", "foo");
+        assert_eq!(provider.region(), Ok(Region::EuWest1));
+
+        provider.set_profile("no_region");
+        assert_eq!(provider.region(), Err(RegionError::new("No region in profile")));
+    }
+
+    #[test]
+    fn metadata_regions() {
+        assert_eq!(super::region_from_metadata("us-east-1"), Ok(Region::UsEast1));
+        assert_eq!(super::region_from_metadata("ap-southeast-2\n"), Ok(Region::ApSoutheast2));
+        assert_eq!(super::region_from_metadata("ca-central-1").map(|region| region.name().to_owned()),
+                   Ok("ca-central-1".to_owned()));
+        assert!(super::region_from_metadata("").is_err());
+    }
+
+    #[test]
+    fn region_chain_explicit_profile_provider() {
+        let profile_provider = ProfileRegionProvider::with_configuration("tests/sample-data/multiple_profile_config", "foo");
+
+        // the environment of the machine running the tests mustn't matter, and there's no
+        // metadata service to wait for
+        let mut chain = ChainRegionProvider::with_profile_provider(profile_provider);
+        chain.set_environment_provider(None);
+        chain.set_instance_metadata_provider(None);
+
+        assert_eq!(chain.region(), Ok(Region::EuWest1));
+    }
+
+    #[test]
+    fn region_chain_exhausted() {
+        let profile_provider = ProfileRegionProvider::with_configuration("tests/sample-data/multiple_profile_config", "no_region");
+
+        let mut chain = ChainRegionProvider::with_profile_provider(profile_provider);
+        chain.set_environment_provider(None);
+        chain.set_instance_metadata_provider(None);
+
+        assert_eq!(chain.region(),
+                   Err(RegionError::new("Couldn't find an AWS region in environment, config file, or instance metadata.")));
+    }
+}
@@ -0,0 +1,401 @@
+//! Recording requests and their responses to fixture files, and replaying them.
+//!
+//! Wrap a dispatcher in a `RecordingDispatcher` and run a test against AWS once to record
+//! it, then run it with a `ReplayDispatcher` reading the same file from then on.
+//!
+//! Fixtures are JSON: an array of interactions, each holding the request's method, path,
+//! query parameters, selected headers and body, and the response's status, headers and body.
+//! Signatures, dates and session tokens are never recorded.
+
+use std::ascii::AsciiExt;
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use rustc_serialize::base64::{FromBase64, ToBase64, STANDARD};
+use serde_json::{self, Value};
+
+use request::{DispatchSignedRequest, HttpDispatchError, HttpDispatchErrorKind, HttpResponse};
+use signature::SignedRequest;
+
+macro_rules! try_opt {
+    ($e:expr) => (match $e { Some(value) => value, None => return None })
+}
+
+/// Request headers recorded unless others are chosen with `RecordingDispatcher::with_headers`.
+const DEFAULT_RECORDED_HEADERS: &'static [&'static str] = &["content-type", "x-amz-target"];
+
+/// Headers that change every time a request is signed, or are derived from the credentials.
+/// These are never recorded, whatever headers are chosen.
+const SIGNING_HEADERS: &'static [&'static str] = &[
+    "authorization",
+    "x-amz-content-sha256",
+    "x-amz-date",
+    "x-amz-security-token",
+];
+
+/// Query parameters that change every time a request is signed.
+const SIGNING_PARAMS: &'static [&'static str] = &[
+    "X-Amz-Algorithm",
+    "X-Amz-Credential",
+    "X-Amz-Date",
+    "X-Amz-Expires",
+    "X-Amz-Security-Token",
+    "X-Amz-Signature",
+    "X-Amz-SignedHeaders",
+];
+
+/// The parts of a request a `ReplayDispatcher` compares with the recorded ones.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum MatchOn {
+    Method,
+    Path,
+    /// The query parameters, or the form parameters of query protocol services.
+    Query,
+    /// The headers that were recorded.
+    Headers,
+    Body,
+}
+
+/// Sends requests with another dispatcher and writes every request and its response to a
+/// fixture file.
+///
+/// The file is rewritten after every request, so it is complete even if the test panics.
+pub struct RecordingDispatcher<D> {
+    dispatcher: D,
+    path: PathBuf,
+    headers: Vec<String>,
+    interactions: Mutex<Vec<Interaction>>,
+}
+
+impl <D> RecordingDispatcher<D> where D: DispatchSignedRequest {
+    /// Record the requests sent with `dispatcher` to the file at `path`, replacing it.
+    pub fn new<P>(dispatcher: D, path: P) -> RecordingDispatcher<D> where P: AsRef<Path> {
+        RecordingDispatcher {
+            dispatcher: dispatcher,
+            path: path.as_ref().to_path_buf(),
+            headers: DEFAULT_RECORDED_HEADERS.iter().map(|header| header.to_string()).collect(),
+            interactions: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Record these request headers instead of `content-type` and `x-amz-target`.
+    ///
+    /// `authorization`, `x-amz-content-sha256`, `x-amz-date` and `x-amz-security-token` are
+    /// left out even if they are listed.
+    pub fn with_headers(mut self, headers: &[&str]) -> RecordingDispatcher<D> {
+        self.headers = headers.iter().map(|header| header.to_ascii_lowercase()).collect();
+        self
+    }
+
+    fn save(&self, interactions: &[Interaction]) -> io::Result<()> {
+        let fixture = Value::Array(interactions.iter().map(Interaction::to_json).collect());
+        let mut file = try!(File::create(&self.path));
+        try!(file.write_all(serde_json::to_string_pretty(&fixture).unwrap().as_bytes()));
+        file.write_all(b"\n")
+    }
+}
+
+impl <D> DispatchSignedRequest for RecordingDispatcher<D> where D: DispatchSignedRequest {
+    fn dispatch(&self, request: &SignedRequest) -> Result<HttpResponse, HttpDispatchError> {
+        let response = try!(self.dispatcher.dispatch(request));
+
+        let mut interactions = self.interactions.lock().unwrap();
+        interactions.push(Interaction {
+            request: RecordedRequest::new(request, &self.headers),
+            response: response.clone(),
+        });
+        try!(self.save(&interactions));
+
+        Ok(response)
+    }
+}
+
+/// Answers requests with the responses recorded by a `RecordingDispatcher`, without sending
+/// anything.
+///
+/// Each request gets the response of the first recorded request that matches it and hasn't
+/// been replayed yet, so repeating a request replays the responses it got in order.
+pub struct ReplayDispatcher {
+    match_on: Vec<MatchOn>,
+    interactions: Mutex<Vec<(Interaction, bool)>>,
+}
+
+impl ReplayDispatcher {
+    /// Replay the fixture file at `path`, matching requests on everything that was recorded.
+    pub fn from_file<P>(path: P) -> io::Result<ReplayDispatcher> where P: AsRef<Path> {
+        let mut fixture = String::new();
+        try!(try!(File::open(path)).read_to_string(&mut fixture));
+        ReplayDispatcher::from_json(&fixture)
+    }
+
+    /// Replay a fixture that has already been read.
+    pub fn from_json(fixture: &str) -> io::Result<ReplayDispatcher> {
+        let invalid = |message: &str| io::Error::new(io::ErrorKind::InvalidData, format!("Invalid fixture: {}", message));
+
+        let fixture: Value = try!(serde_json::from_str(fixture).map_err(|err| invalid(&err.to_string())));
+        let interactions = try!(fixture.as_array().ok_or(invalid("expected an array of interactions")));
+
+        let mut parsed = Vec::with_capacity(interactions.len());
+        for interaction in interactions {
+            parsed.push((try!(Interaction::from_json(interaction).ok_or(invalid("malformed interaction"))), false));
+        }
+
+        Ok(ReplayDispatcher {
+            match_on: vec![MatchOn::Method, MatchOn::Path, MatchOn::Query, MatchOn::Headers, MatchOn::Body],
+            interactions: Mutex::new(parsed),
+        })
+    }
+
+    /// Only compare these parts of requests with the recorded ones.
+    pub fn match_on(mut self, match_on: &[MatchOn]) -> ReplayDispatcher {
+        self.match_on = match_on.to_vec();
+        self
+    }
+
+    /// The number of recorded responses that haven't been replayed.
+    pub fn remaining(&self) -> usize {
+        self.interactions.lock().unwrap().iter().filter(|&&(_, replayed)| !replayed).count()
+    }
+}
+
+impl DispatchSignedRequest for ReplayDispatcher {
+    fn dispatch(&self, request: &SignedRequest) -> Result<HttpResponse, HttpDispatchError> {
+        let mut interactions = self.interactions.lock().unwrap();
+
+        for &mut (ref interaction, ref mut replayed) in interactions.iter_mut() {
+            if !*replayed && interaction.request.matches(request, &self.match_on) {
+                *replayed = true;
+                return Ok(interaction.response.clone());
+            }
+        }
+
+        Err(HttpDispatchError::new(HttpDispatchErrorKind::Other,
+            format!("No recorded response left for {} {}", request.method(), request.path())))
+    }
+}
+
+struct Interaction {
+    request: RecordedRequest,
+    response: HttpResponse,
+}
+
+struct RecordedRequest {
+    method: String,
+    path: String,
+    params: BTreeMap<String, String>,
+    headers: BTreeMap<String, String>,
+    body: Vec<u8>,
+}
+
+impl RecordedRequest {
+    fn new(request: &SignedRequest, headers: &[String]) -> RecordedRequest {
+        RecordedRequest {
+            method: request.method().to_owned(),
+            path: request.path().to_owned(),
+            params: request_params(request),
+            headers: request_headers(request, headers),
+            body: request.payload().unwrap_or(&[]).to_vec(),
+        }
+    }
+
+    fn matches(&self, request: &SignedRequest, match_on: &[MatchOn]) -> bool {
+        match_on.iter().all(|part| match *part {
+            MatchOn::Method => self.method == request.method(),
+            MatchOn::Path => self.path == request.path(),
+            MatchOn::Query => self.params == request_params(request),
+            MatchOn::Headers => {
+                let names = self.headers.keys().cloned().collect::<Vec<String>>();
+                self.headers == request_headers(request, &names)
+            }
+            MatchOn::Body => &self.body[..] == request.payload().unwrap_or(&[]),
+        })
+    }
+}
+
+fn request_params(request: &SignedRequest) -> BTreeMap<String, String> {
+    request.params.iter()
+        .filter(|&(key, _)| !SIGNING_PARAMS.contains(&&key[..]))
+        .map(|(key, value)| (key.clone(), value.clone()))
+        .collect()
+}
+
+fn request_headers(request: &SignedRequest, names: &[String]) -> BTreeMap<String, String> {
+    request.headers().iter()
+        .filter(|&(name, _)| names.contains(name) && !SIGNING_HEADERS.contains(&&name[..]))
+        .map(|(name, values)| {
+            let values = values.iter().map(|value| String::from_utf8_lossy(value).into_owned()).collect::<Vec<String>>();
+            (name.clone(), values.join(","))
+        })
+        .collect()
+}
+
+impl Interaction {
+    fn to_json(&self) -> Value {
+        let mut request = BTreeMap::new();
+        request.insert("method".to_owned(), Value::String(self.request.method.clone()));
+        request.insert("path".to_owned(), Value::String(self.request.path.clone()));
+        request.insert("params".to_owned(), string_map_to_json(&self.request.params));
+        request.insert("headers".to_owned(), string_map_to_json(&self.request.headers));
+        insert_body(&mut request, &self.request.body);
+
+        let mut response = BTreeMap::new();
+        response.insert("status".to_owned(), Value::U64(self.response.status as u64));
+        response.insert("headers".to_owned(), string_map_to_json(&self.response.headers.iter().collect::<BTreeMap<_, _>>()));
+        insert_body(&mut response, &self.response.body);
+
+        let mut interaction = BTreeMap::new();
+        interaction.insert("request".to_owned(), Value::Object(request));
+        interaction.insert("response".to_owned(), Value::Object(response));
+        Value::Object(interaction)
+    }
+
+    fn from_json(json: &Value) -> Option<Interaction> {
+        let request = try_opt!(json.find("request"));
+        let response = try_opt!(json.find("response"));
+
+        let recorded = RecordedRequest {
+            method: try_opt!(request.find("method").and_then(Value::as_str)).to_owned(),
+            path: try_opt!(request.find("path").and_then(Value::as_str)).to_owned(),
+            params: try_opt!(request.find("params").and_then(json_to_string_map)),
+            headers: try_opt!(request.find("headers").and_then(json_to_string_map)),
+            body: try_opt!(extract_body(request)),
+        };
+
+        let mut replayed = HttpResponse::default();
+        replayed.status = try_opt!(response.find("status").and_then(Value::as_u64)) as u16;
+        replayed.headers = try_opt!(response.find("headers").and_then(json_to_string_map)).into_iter().collect();
+        replayed.body = try_opt!(extract_body(response));
+
+        Some(Interaction {
+            request: recorded,
+            response: replayed,
+        })
+    }
+}
+
+fn string_map_to_json<K, V>(map: &BTreeMap<K, V>) -> Value where K: AsRef<str>, V: AsRef<str> {
+    Value::Object(map.iter().map(|(key, value)| (key.as_ref().to_owned(), Value::String(value.as_ref().to_owned()))).collect())
+}
+
+fn json_to_string_map(json: &Value) -> Option<BTreeMap<String, String>> {
+    json.as_object().and_then(|object| {
+        object.iter()
+            .map(|(key, value)| value.as_str().map(|value| (key.clone(), value.to_owned())))
+            .collect()
+    })
+}
+
+/// Bodies are stored as text when they are UTF-8, which is nearly always, and as base64
+/// otherwise.
+fn insert_body(object: &mut BTreeMap<String, Value>, body: &[u8]) {
+    match String::from_utf8(body.to_vec()) {
+        Ok(text) => object.insert("body".to_owned(), Value::String(text)),
+        Err(_) => object.insert("body_base64".to_owned(), Value::String(body.to_base64(STANDARD))),
+    };
+}
+
+fn extract_body(object: &Value) -> Option<Vec<u8>> {
+    if let Some(body) = object.find("body").and_then(Value::as_str) {
+        return Some(body.as_bytes().to_vec());
+    }
+    object.find("body_base64")
+        .and_then(Value::as_str)
+        .and_then(|body| body.from_base64().ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+    use std::fs;
+
+    use rand;
+
+    use mock::MockRequestDispatcher;
+    use param::ServiceParams;
+    use region::Region;
+    use request::DispatchSignedRequest;
+    use signature::SignedRequest;
+    use super::*;
+
+    fn send_message_request<'a>(body: &str) -> SignedRequest<'a> {
+        let mut request = SignedRequest::new("POST", "sqs", Region::UsEast1, "/");
+        request.add_header("x-amz-date", "20160101T000000Z");
+        request.params.put("Action", "SendMessage");
+        request.params.put("MessageBody", body);
+        request.params.put("X-Amz-Signature", "0123456789abcdef");
+        request
+    }
+
+    #[test]
+    fn record_and_replay() {
+        let path = env::temp_dir().join(format!("rusoto-replay-test-{}.json", rand::random::<u32>()));
+
+        {
+            let recorder = RecordingDispatcher::new(MockRequestDispatcher::with_status(200).with_body("<SendMessageResponse/>"), &path);
+            recorder.dispatch(&send_message_request("hello")).unwrap();
+        }
+
+        let replayer = ReplayDispatcher::from_file(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        let mut request = send_message_request("hello");
+        request.add_header("x-amz-date", "20170101T000000Z");
+        request.params.put("X-Amz-Signature", "fedcba9876543210");
+
+        let response = replayer.dispatch(&request).unwrap();
+        assert_eq!(response.status, 200);
+        assert_eq!(response.body, b"<SendMessageResponse/>".to_vec());
+
+        // each recorded response is only replayed once
+        assert!(replayer.dispatch(&request).is_err());
+    }
+
+    #[test]
+    fn signing_headers_are_never_recorded() {
+        let path = env::temp_dir().join(format!("rusoto-replay-test-{}.json", rand::random::<u32>()));
+
+        let mut request = send_message_request("hello");
+        request.add_header("authorization", "AWS4-HMAC-SHA256 Credential=AKIDEXAMPLE/20160101/us-east-1/sqs/aws4_request");
+        request.add_header("x-amz-security-token", "session-token");
+        request.add_header("x-amz-content-sha256", "UNSIGNED-PAYLOAD");
+        request.add_header("content-type", "application/x-www-form-urlencoded");
+
+        {
+            let recorder = RecordingDispatcher::new(MockRequestDispatcher::with_status(200), &path)
+                .with_headers(&["Authorization", "x-amz-security-token", "x-amz-content-sha256", "x-amz-date", "content-type"]);
+            recorder.dispatch(&request).unwrap();
+        }
+
+        let mut fixture = String::new();
+        File::open(&path).unwrap().read_to_string(&mut fixture).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert!(fixture.contains("application/x-www-form-urlencoded"));
+        for secret in &["AKIDEXAMPLE", "session-token", "UNSIGNED-PAYLOAD", "20160101T000000Z"] {
+            assert!(!fixture.contains(secret), "{} was recorded", secret);
+        }
+    }
+
+    #[test]
+    fn configurable_matching() {
+        let fixture = r#"[{
+            "request": {"method": "POST", "path": "/", "params": {"Action": "SendMessage", "MessageBody": "hello"}, "headers": {}, "body": ""},
+            "response": {"status": 200, "headers": {"x-amzn-requestid": "abc"}, "body_base64": "AAEC"}
+        }]"#;
+
+        let replayer = ReplayDispatcher::from_json(fixture).unwrap();
+        assert!(replayer.dispatch(&send_message_request("goodbye")).is_err());
+        assert_eq!(replayer.remaining(), 1);
+
+        let replayer = ReplayDispatcher::from_json(fixture).unwrap().match_on(&[MatchOn::Method, MatchOn::Path]);
+        let response = replayer.dispatch(&send_message_request("goodbye")).unwrap();
+        assert_eq!(response.body, vec![0, 1, 2]);
+        assert_eq!(response.headers["x-amzn-requestid"], "abc");
+        assert_eq!(replayer.remaining(), 0);
+
+        assert!(ReplayDispatcher::from_json("{\"not\": \"an array\"}").is_err());
+    }
+}
@@ -5,20 +5,26 @@
 extern crate lazy_static;
 
 use std::env;
-use std::io::Read;
-use std::io::Error as IoError;
+use std::io::{Cursor, Read};
+use std::io::{Error as IoError, ErrorKind as IoErrorKind};
 use std::error::Error;
 use std::fmt;
 use std::collections::HashMap;
+use std::time::Duration;
 
 use hyper::Client;
+use hyper::client::{Body, RedirectPolicy, Response};
+use hyper::client::pool::Pool;
 use hyper::Error as HyperError;
+use hyper::net::Openssl;
 use hyper::header::Headers;
 use hyper::header::UserAgent;
 use hyper::method::Method;
 
 use log::LogLevel::Debug;
 
+use connector::{ConnectError, Connector, ResolveError, with_deadline};
+use proxy::ProxyConfig;
 use signature::SignedRequest;
 
 // Pulls in the statically generated rustc version.
@@ -27,116 +33,560 @@ include!(concat!(env!("OUT_DIR"), "/user_agent_vars.rs"));
 // Use a lazy static to cache the default User-Agent header
 // because it never changes once it's been computed.
 lazy_static! {
-    static ref DEFAULT_USER_AGENT: Vec<Vec<u8>> = vec![format!("rusoto/{} rust/{} {}",
+    pub static ref DEFAULT_USER_AGENT: Vec<Vec<u8>> = vec![format!("rusoto/{} rust/{} {}",
             env!("CARGO_PKG_VERSION"), RUST_VERSION, env::consts::OS).as_bytes().to_vec()];
 }
 
 #[derive(Clone, Default)]
 pub struct HttpResponse {
     pub status: u16,
-    pub body: String,
+    pub body: Vec<u8>,
     pub headers: HashMap<String, String>
 }
 
-#[derive(Debug, PartialEq)]
+/// A response whose body is read from the connection on demand instead of being buffered.
+pub struct HttpStreamingResponse {
+    pub status: u16,
+    pub body: Box<Read + Send>,
+    pub headers: HashMap<String, String>
+}
+
+impl HttpStreamingResponse {
+    /// Read the rest of the body into memory.
+    pub fn buffer(mut self) -> Result<HttpResponse, HttpDispatchError> {
+        let mut body = Vec::new();
+        try!(self.body.read_to_end(&mut body).map_err(body_read_error));
+
+        Ok(HttpResponse {
+            status: self.status,
+            body: body,
+            headers: self.headers
+        })
+    }
+}
+
+impl From<HttpResponse> for HttpStreamingResponse {
+    fn from(response: HttpResponse) -> HttpStreamingResponse {
+        HttpStreamingResponse {
+            status: response.status,
+            body: Box::new(Cursor::new(response.body)),
+            headers: response.headers
+        }
+    }
+}
+
+/// The broad category of an `HttpDispatchError`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum HttpDispatchErrorKind {
+    /// Connecting, sending the request or reading the response took longer than allowed.
+    Timeout,
+    /// The host name couldn't be resolved.
+    Dns,
+    /// Nothing accepted the connection.
+    ConnectionRefused,
+    /// The connection was reset or closed before the response was complete.
+    ConnectionReset,
+    /// Negotiating TLS failed, for example because the certificate was rejected.
+    Tls,
+    /// The status and headers of the response arrived, but reading its body failed for a
+    /// reason other than a timeout.
+    BodyRead,
+    /// The request can't be sent as it is, for example because its method or URI is invalid.
+    /// Retrying it won't help.
+    InvalidRequest,
+    /// The response wasn't valid HTTP.
+    InvalidResponse,
+    Other,
+}
+
+/// An error sending a request or receiving its response.
+///
+/// The error that caused it, if any, is available from `cause`.
+#[derive(Debug)]
 pub struct HttpDispatchError {
-    message: String
+    kind: HttpDispatchErrorKind,
+    message: String,
+    uri: Option<String>,
+    request_sent: bool,
+    cause: Option<Box<Error + Send + Sync>>,
+}
+
+impl HttpDispatchError {
+    pub fn new<S>(kind: HttpDispatchErrorKind, message: S) -> HttpDispatchError where S: Into<String> {
+        HttpDispatchError {
+            kind: kind,
+            message: message.into(),
+            uri: None,
+            request_sent: false,
+            cause: None,
+        }
+    }
+
+    /// Record that the request may have reached the service before the error, so sending it
+    /// again could apply it twice.
+    pub fn with_request_sent(mut self) -> HttpDispatchError {
+        self.request_sent = true;
+        self
+    }
+
+    /// Record the URI the request was being sent to.
+    pub fn with_uri<S>(mut self, uri: S) -> HttpDispatchError where S: Into<String> {
+        self.uri = Some(uri.into());
+        self
+    }
+
+    /// Keep the error that caused this one.
+    pub fn with_cause<E>(mut self, cause: E) -> HttpDispatchError where E: Into<Box<Error + Send + Sync>> {
+        self.cause = Some(cause.into());
+        self
+    }
+
+    pub fn kind(&self) -> HttpDispatchErrorKind {
+        self.kind
+    }
+
+    /// The URI the request was being sent to, if it got that far.
+    pub fn uri(&self) -> Option<&str> {
+        self.uri.as_ref().map(|uri| &uri[..])
+    }
+
+    pub fn is_timeout(&self) -> bool {
+        self.kind == HttpDispatchErrorKind::Timeout
+    }
+
+    /// Whether the request may have reached the service before the error.
+    pub fn request_sent(&self) -> bool {
+        self.request_sent
+    }
+
+    /// Whether sending the request again is safe and might succeed: timeouts, DNS failures,
+    /// refused connections and resets, as long as the request wasn't sent.
+    ///
+    /// Once a request has been sent, sending it again could apply a `PUT` or `POST` twice, and
+    /// errors such as a rejected certificate or an invalid response won't go away on their own.
+    pub fn is_retryable(&self) -> bool {
+        if self.request_sent {
+            return false;
+        }
+
+        match self.kind {
+            HttpDispatchErrorKind::Timeout | HttpDispatchErrorKind::Dns |
+                HttpDispatchErrorKind::ConnectionRefused | HttpDispatchErrorKind::ConnectionReset => true,
+            _ => false,
+        }
+    }
+}
+
+impl PartialEq for HttpDispatchError {
+    /// Errors are equal if their kind, message and URI are; causes aren't compared.
+    fn eq(&self, other: &HttpDispatchError) -> bool {
+        self.kind == other.kind && self.message == other.message && self.uri == other.uri
+    }
 }
 
 impl Error for HttpDispatchError {
     fn description(&self) -> &str {
         &self.message
     }
+
+    fn cause(&self) -> Option<&Error> {
+        self.cause.as_ref().map(|cause| &**cause as &Error)
+    }
 }
 
 impl fmt::Display for HttpDispatchError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{}", self.message)
+        match self.uri {
+            Some(ref uri) => write!(f, "{} ({})", self.message, uri),
+            None => write!(f, "{}", self.message),
+        }
     }
 }
 
 impl From<HyperError> for HttpDispatchError {
     fn from(err: HyperError) -> HttpDispatchError {
-        HttpDispatchError { message: err.description().to_string() }
+        let kind = match err {
+            HyperError::Io(err) => return HttpDispatchError::from(err),
+            HyperError::Ssl(_) => HttpDispatchErrorKind::Tls,
+            HyperError::Method | HyperError::Uri(_) => HttpDispatchErrorKind::InvalidRequest,
+            HyperError::Version | HyperError::Header | HyperError::TooLarge | HyperError::Status | HyperError::Utf8(_) => {
+                HttpDispatchErrorKind::InvalidResponse
+            }
+            _ => HttpDispatchErrorKind::Other,
+        };
+        let message = err.to_string();
+        HttpDispatchError::new(kind, message).with_cause(err)
     }
 }
 
 impl From<IoError> for HttpDispatchError {
     fn from(err: IoError) -> HttpDispatchError {
-        HttpDispatchError { message: err.description().to_string() }
+        let resolving = err.get_ref().map_or(false, |inner| inner.is::<ResolveError>());
+        let connecting = err.get_ref().map_or(false, |inner| inner.is::<ConnectError>());
+
+        let kind = match err.kind() {
+            _ if resolving => HttpDispatchErrorKind::Dns,
+            // socket read and write timeouts show up as WouldBlock on some platforms
+            IoErrorKind::TimedOut | IoErrorKind::WouldBlock => HttpDispatchErrorKind::Timeout,
+            IoErrorKind::ConnectionRefused => HttpDispatchErrorKind::ConnectionRefused,
+            IoErrorKind::ConnectionReset | IoErrorKind::ConnectionAborted |
+                IoErrorKind::BrokenPipe | IoErrorKind::UnexpectedEof => HttpDispatchErrorKind::ConnectionReset,
+            IoErrorKind::InvalidInput => HttpDispatchErrorKind::InvalidRequest,
+            _ => HttpDispatchErrorKind::Other
+        };
+        let message = err.to_string();
+        let dispatch_error = HttpDispatchError::new(kind, message).with_cause(err);
+
+        // only errors resolving the host or connecting to it are known to come before the
+        // request was written
+        if resolving || connecting {
+            dispatch_error
+        } else {
+            dispatch_error.with_request_sent()
+        }
+    }
+}
+
+/// Classify an error reading the body of a response.
+fn body_read_error(err: IoError) -> HttpDispatchError {
+    let err = HttpDispatchError::from(err);
+    if err.is_timeout() {
+        return err;
+    }
+    HttpDispatchError { kind: HttpDispatchErrorKind::BodyRead, ..err }
+}
+
+/// Settings for the `hyper::Client` requests are sent with.
+///
+/// By default connecting times out after 10 seconds, and a read or write on an open
+/// connection after 60 seconds.
+#[derive(Clone, Debug, PartialEq)]
+pub struct HttpConfig {
+    connect_timeout: Option<Duration>,
+    read_timeout: Option<Duration>,
+    write_timeout: Option<Duration>,
+    proxies: Vec<ProxyConfig>,
+}
+
+impl Default for HttpConfig {
+    fn default() -> HttpConfig {
+        HttpConfig {
+            connect_timeout: Some(Duration::from_secs(10)),
+            read_timeout: Some(Duration::from_secs(60)),
+            write_timeout: Some(Duration::from_secs(60)),
+            proxies: Vec::new(),
+        }
     }
 }
 
+impl HttpConfig {
+    /// The default settings, with the proxies configured in the environment, if any.
+    pub fn from_env() -> HttpConfig {
+        HttpConfig::default().with_proxies(ProxyConfig::from_env())
+    }
+
+    /// Set how long to wait for a TCP connection to open.  `None` waits indefinitely.
+    pub fn with_connect_timeout(mut self, timeout: Option<Duration>) -> HttpConfig {
+        self.connect_timeout = timeout;
+        self
+    }
+
+    /// Set how long a single read from an open connection may block.  `None` waits indefinitely.
+    pub fn with_read_timeout(mut self, timeout: Option<Duration>) -> HttpConfig {
+        self.read_timeout = timeout;
+        self
+    }
+
+    /// Set how long a single write to an open connection may block.  `None` waits indefinitely.
+    pub fn with_write_timeout(mut self, timeout: Option<Duration>) -> HttpConfig {
+        self.write_timeout = timeout;
+        self
+    }
+
+    /// Send requests through `proxy`, instead of any proxies set before.
+    pub fn with_proxy(mut self, proxy: Option<ProxyConfig>) -> HttpConfig {
+        self.proxies = proxy.into_iter().collect();
+        self
+    }
+
+    /// Send requests through the first of `proxies` that applies to them.
+    pub fn with_proxies(mut self, proxies: Vec<ProxyConfig>) -> HttpConfig {
+        self.proxies = proxies;
+        self
+    }
+
+    pub fn connect_timeout(&self) -> Option<Duration> {
+        self.connect_timeout
+    }
+
+    pub fn read_timeout(&self) -> Option<Duration> {
+        self.read_timeout
+    }
+
+    pub fn write_timeout(&self) -> Option<Duration> {
+        self.write_timeout
+    }
+
+    pub fn proxies(&self) -> &[ProxyConfig] {
+        &self.proxies
+    }
+
+    /// The proxy a request to a URL with `scheme` and `host` goes through, if any.
+    pub fn proxy_for(&self, scheme: &str, host: &str) -> Option<&ProxyConfig> {
+        self.proxies.iter().find(|proxy| proxy.applies_to(scheme, host))
+    }
+
+    /// Build a `hyper::Client` with these settings.  It doesn't follow redirects.
+    pub fn client(&self) -> Client {
+        let connector = Connector::new(Openssl::default())
+            .with_proxies(self.proxies.clone())
+            .with_connect_timeout(self.connect_timeout);
+
+        let mut client = Client::with_connector(Pool::with_connector(Default::default(), connector));
+        client.set_read_timeout(self.read_timeout);
+        client.set_write_timeout(self.write_timeout);
+        client.set_redirect_policy(RedirectPolicy::FollowNone);
+        client
+    }
+}
+
+/// The `hyper::Client` clients are created with by `new`.
+///
+/// It uses the default `HttpConfig`, going through the proxies configured with `HTTPS_PROXY`,
+/// `HTTP_PROXY` and `NO_PROXY`, if any.
+pub fn default_client() -> Client {
+    HttpConfig::from_env().client()
+}
+
 pub trait DispatchSignedRequest {
     fn dispatch(&self, request: &SignedRequest) -> Result<HttpResponse, HttpDispatchError>;
+
+    /// Send the request, returning as soon as the status and headers have been received.
+    ///
+    /// The default implementation buffers the response with `dispatch`.
+    fn dispatch_streaming(&self, request: &SignedRequest) -> Result<HttpStreamingResponse, HttpDispatchError> {
+        self.dispatch(request).map(HttpStreamingResponse::from)
+    }
 }
 
 impl DispatchSignedRequest for Client {
     fn dispatch(&self, request: &SignedRequest) -> Result<HttpResponse, HttpDispatchError> {
-        let hyper_method = match request.method().as_ref() {
-            "POST" => Method::Post,
-            "PUT" => Method::Put,
-            "DELETE" => Method::Delete,
-            "GET" => Method::Get,
-            "HEAD" => Method::Head,
-            v @ _ => return Err(HttpDispatchError { message: format!("Unsupported HTTP verb {}", v) })
+        let (hyper_response, body) = try!(with_deadline(request.deadline(), || -> Result<(Response, Vec<u8>), HttpDispatchError> {
+            let mut hyper_response = try!(send_request(self, request));
 
-        };
+            let mut body = Vec::new();
+            try!(hyper_response.read_to_end(&mut body).map_err(|err| body_read_error(err).with_uri(request_uri(request))));
+            Ok((hyper_response, body))
+        }));
 
-        // translate the headers map to a format Hyper likes
-        let mut hyper_headers = Headers::new();
-        for h in request.headers().iter() {
-            hyper_headers.set_raw(h.0.to_owned(), h.1.to_owned());
+        if log_enabled!(Debug) {
+            debug!("Response body:\n{}", String::from_utf8_lossy(&body));
         }
 
-        // Add a default user-agent header if one is not already present.
-        if !hyper_headers.has::<UserAgent>() {
-            hyper_headers.set_raw("user-agent".to_owned(), DEFAULT_USER_AGENT.clone());
-        }
+        Ok(HttpResponse {
+            status: hyper_response.status.to_u16(),
+            body: body,
+            headers: response_headers(&hyper_response)
+        })
+    }
 
-        let mut final_uri = format!("https://{}{}", request.hostname(), request.path());
-        if !request.canonical_query_string().is_empty() {
-            final_uri = final_uri + &format!("?{}", request.canonical_query_string());
-        }
+    fn dispatch_streaming(&self, request: &SignedRequest) -> Result<HttpStreamingResponse, HttpDispatchError> {
+        // the body is read after this returns, so only sending is bounded by the deadline
+        let hyper_response = try!(with_deadline(request.deadline(), || send_request(self, request)));
 
-        if log_enabled!(Debug) {
-            let payload = request.payload().map(|mut payload_bytes| {
-                let mut payload_string = String::new();
+        Ok(HttpStreamingResponse {
+            status: hyper_response.status.to_u16(),
+            headers: response_headers(&hyper_response),
+            body: Box::new(hyper_response)
+        })
+    }
+}
 
-                payload_bytes.read_to_string(&mut payload_string)
-                    .map(|_| payload_string)
-                    .unwrap_or_else(|_| String::from("<non-UTF-8 data>"))
-            });
+/// The URI `request` is sent to.
+pub fn request_uri(request: &SignedRequest) -> String {
+    let mut final_uri = format!("{}://{}{}", request.scheme(), request.hostname(), request.path());
+    if !request.canonical_query_string().is_empty() {
+        final_uri = final_uri + &format!("?{}", request.canonical_query_string());
+    }
+    final_uri
+}
 
-            debug!("Full request: \n method: {}\n final_uri: {}\n payload: {:?}\nHeaders:\n", hyper_method, final_uri, payload);
-            for h in hyper_headers.iter() {
-                debug!("{}:{}", h.name(), h.value_string());
-            }
+/// Fail requests for a `Region::Custom` whose endpoint isn't a valid URL, rather than send
+/// them to the default AWS endpoint for the region's name.
+pub fn check_region_endpoint(request: &SignedRequest) -> Result<(), HttpDispatchError> {
+    match request.region.custom_endpoint() {
+        Ok(_) => Ok(()),
+        Err(err) => Err(HttpDispatchError::new(HttpDispatchErrorKind::InvalidRequest,
+            format!("Region {} has an invalid endpoint", request.region)).with_cause(err)),
+    }
+}
+
+fn send_request(client: &Client, request: &SignedRequest) -> Result<Response, HttpDispatchError> {
+    try!(check_region_endpoint(request));
+    let final_uri = request_uri(request);
+    send_request_to(client, request, &final_uri).map_err(|err| err.with_uri(final_uri.clone()))
+}
+
+fn send_request_to(client: &Client, request: &SignedRequest, final_uri: &str) -> Result<Response, HttpDispatchError> {
+    let hyper_method = match request.method().as_ref() {
+        "POST" => Method::Post,
+        "PUT" => Method::Put,
+        "DELETE" => Method::Delete,
+        "GET" => Method::Get,
+        "HEAD" => Method::Head,
+        v @ _ => return Err(HttpDispatchError::new(HttpDispatchErrorKind::InvalidRequest, format!("Unsupported HTTP verb {}", v)))
+
+    };
+
+    // translate the headers map to a format Hyper likes
+    let mut hyper_headers = Headers::new();
+    for h in request.headers().iter() {
+        hyper_headers.set_raw(h.0.to_owned(), h.1.to_owned());
+    }
+
+    // Add a default user-agent header if one is not already present.
+    if !hyper_headers.has::<UserAgent>() {
+        hyper_headers.set_raw("user-agent".to_owned(), DEFAULT_USER_AGENT.clone());
+    }
+
+    if log_enabled!(Debug) {
+        let payload = request.payload().map(|mut payload_bytes| {
+            let mut payload_string = String::new();
+
+            payload_bytes.read_to_string(&mut payload_string)
+                .map(|_| payload_string)
+                .unwrap_or_else(|_| String::from("<non-UTF-8 data>"))
+        });
+
+        debug!("Full request: \n method: {}\n final_uri: {}\n payload: {:?}\nHeaders:\n", hyper_method, final_uri, payload);
+        for h in hyper_headers.iter() {
+            debug!("{}:{}", h.name(), h.value_string());
         }
+    }
 
-        let mut hyper_response = match request.payload() {
-            None => try!(self.request(hyper_method, &final_uri).headers(hyper_headers).body("").send()),
-            Some(payload_contents) => try!(self.request(hyper_method, &final_uri).headers(hyper_headers).body(payload_contents).send()),
+    if let Some(stream) = request.payload_stream() {
+        let length = stream.encoded_length();
+        let mut reader = match stream.chunked_reader() {
+            Some(reader) => reader,
+            None => return Err(HttpDispatchError::new(HttpDispatchErrorKind::InvalidRequest, "Streamed payloads must be signed before they are sent"))
         };
 
-        let mut body = String::new();
-        try!(hyper_response.read_to_string(&mut body));
+        return Ok(try!(client.request(hyper_method, final_uri).headers(hyper_headers).body(Body::SizedBody(&mut reader, length)).send()));
+    }
 
-        if log_enabled!(Debug) {
-            debug!("Response body:\n{}", body);
-        }
+    let hyper_response = match request.payload() {
+        None => try!(client.request(hyper_method, final_uri).headers(hyper_headers).body("").send()),
+        Some(payload_contents) => try!(client.request(hyper_method, final_uri).headers(hyper_headers).body(payload_contents).send()),
+    };
 
-        let mut headers: HashMap<String, String> = HashMap::new();
+    Ok(hyper_response)
+}
 
-        for header in hyper_response.headers.iter() {
-            headers.insert(header.name().to_string(), header.value_string());
-        }
+fn response_headers(response: &Response) -> HashMap<String, String> {
+    let mut headers: HashMap<String, String> = HashMap::new();
 
-        Ok(HttpResponse {
-            status: hyper_response.status.to_u16(),
-            body: body,
-            headers: headers
-        })
+    for header in response.headers.iter() {
+        headers.insert(header.name().to_string(), header.value_string());
+    }
+
+    headers
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Error as IoError, ErrorKind as IoErrorKind};
+    use std::net::TcpListener;
+    use std::thread;
+    use std::time::{Duration, Instant};
+
+    use hyper::Error as HyperError;
+
+    use region::Region;
+    use super::*;
+
+    #[test]
+    fn timeouts_are_distinguishable() {
+        let err = HttpDispatchError::from(HyperError::from(IoError::new(IoErrorKind::TimedOut, "timed out")));
+        assert_eq!(err.kind(), HttpDispatchErrorKind::Timeout);
+        assert!(err.is_timeout());
+
+        let err = HttpDispatchError::from(IoError::new(IoErrorKind::WouldBlock, "would block"));
+        assert!(err.is_timeout());
+
+        let err = HttpDispatchError::from(IoError::new(IoErrorKind::ConnectionRefused, "refused"));
+        assert_eq!(err.kind(), HttpDispatchErrorKind::ConnectionRefused);
+    }
+
+    #[test]
+    fn deadline_cuts_attempts_short() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        // accept the connection, but never answer
+        thread::spawn(move || {
+            let _stream = listener.accept().unwrap();
+            thread::sleep(Duration::from_secs(10));
+        });
+
+        let mut request = SignedRequest::new("GET", "sqs", Region::UsEast1, "/");
+        request.set_endpoint(Some(format!("http://127.0.0.1:{}", port).parse().unwrap()));
+        request.set_deadline(Some(Instant::now() + Duration::from_millis(200)));
+
+        let started = Instant::now();
+        let err = HttpConfig::default().client().dispatch(&request).unwrap_err();
+
+        assert!(err.is_timeout());
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+
+    #[test]
+    fn errors_are_classified() {
+        let err = HttpDispatchError::from(IoError::new(IoErrorKind::Other, ResolveError::new("nowhere.invalid", IoError::new(IoErrorKind::Other, "no such host"))));
+        assert_eq!(err.kind(), HttpDispatchErrorKind::Dns);
+
+        let err = HttpDispatchError::from(HyperError::Ssl(Box::new(IoError::new(IoErrorKind::Other, "certificate verify failed"))));
+        assert_eq!(err.kind(), HttpDispatchErrorKind::Tls);
+
+        let err = body_read_error(IoError::new(IoErrorKind::ConnectionReset, "reset"));
+        assert_eq!(err.kind(), HttpDispatchErrorKind::BodyRead);
+        assert!(body_read_error(IoError::new(IoErrorKind::TimedOut, "timed out")).is_timeout());
+
+        let err = HttpDispatchError::from(HyperError::Method).with_uri("https://sqs.us-east-1.amazonaws.com/");
+        assert!(!err.is_retryable());
+        assert_eq!(err.uri(), Some("https://sqs.us-east-1.amazonaws.com/"));
+        assert!(err.to_string().ends_with("(https://sqs.us-east-1.amazonaws.com/)"));
+        assert!(err.cause().is_some());
+    }
+
+    #[test]
+    fn invalid_custom_endpoints_fail() {
+        let region = Region::Custom { name: "local".to_owned(), endpoint: Some("localhost:8000".to_owned()) };
+        let request = SignedRequest::new("POST", "dynamodb", region, "/");
+
+        let err = HttpConfig::default().client().dispatch(&request).unwrap_err();
+        assert_eq!(err.kind(), HttpDispatchErrorKind::InvalidRequest);
+        assert_eq!(err.to_string(), "Region local has an invalid endpoint");
+    }
+
+    #[test]
+    fn only_errors_before_sending_are_retryable() {
+        let refused = IoError::new(IoErrorKind::ConnectionRefused, "refused");
+        let err = HttpDispatchError::from(IoError::new(IoErrorKind::ConnectionRefused, ConnectError::new("localhost", 80, refused)));
+        assert!(!err.request_sent() && err.is_retryable());
+
+        let connect_timeout = IoError::new(IoErrorKind::TimedOut, "timed out");
+        let err = HttpDispatchError::from(IoError::new(IoErrorKind::TimedOut, ConnectError::new("localhost", 80, connect_timeout)));
+        assert!(err.is_timeout() && err.is_retryable());
+
+        let err = HttpDispatchError::from(HyperError::from(IoError::new(IoErrorKind::TimedOut, "timed out")));
+        assert!(err.is_timeout() && err.request_sent() && !err.is_retryable());
+
+        let err = HttpDispatchError::from(IoError::new(IoErrorKind::ConnectionReset, "reset"));
+        assert!(err.request_sent() && !err.is_retryable());
 
+        assert!(HttpDispatchError::new(HttpDispatchErrorKind::ConnectionReset, "reset").is_retryable());
+        assert!(!HttpDispatchError::from(HyperError::Ssl(Box::new(IoError::new(IoErrorKind::Other, "certificate verify failed")))).is_retryable());
+        assert!(!HttpDispatchError::from(HyperError::Status).is_retryable());
+        assert!(!HttpDispatchError::new(HttpDispatchErrorKind::Other, "unknown").is_retryable());
     }
 }
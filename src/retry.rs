@@ -0,0 +1,573 @@
+//! Retry policies for AWS API requests.
+//!
+//! Requests that are throttled or that fail with a transient server error are re-signed
+//! and re-dispatched with exponential backoff and jitter.
+
+use std::cmp;
+use std::fmt;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use rand::{self, Rng};
+use regex::Regex;
+use serde_json::{Value, from_str};
+
+use credential::AwsCredentials;
+use interceptor::InterceptorChain;
+use request::{DispatchSignedRequest, HttpDispatchError, HttpResponse, HttpStreamingResponse};
+use signature::SignedRequest;
+
+/// Error codes AWS services use to signal that a request was throttled.
+const THROTTLING_ERROR_CODES: &'static [&'static str] = &[
+    "Throttling",
+    "ThrottlingException",
+    "ThrottledException",
+    "RequestThrottledException",
+    "TooManyRequestsException",
+    "ProvisionedThroughputExceededException",
+    "TransactionInProgressException",
+    "RequestLimitExceeded",
+    "BandwidthLimitExceeded",
+    "LimitExceededException",
+    "RequestThrottled",
+    "SlowDown",
+    "PriorRequestNotComplete",
+    "EC2ThrottledException",
+];
+
+/// HTTP status codes that indicate a transient server side failure.
+const RETRYABLE_STATUS_CODES: &'static [u16] = &[500, 502, 503, 504];
+
+const DEFAULT_MAX_ATTEMPTS: u32 = 3;
+const DEFAULT_BASE_DELAY_MS: u64 = 100;
+const DEFAULT_MAX_DELAY_MS: u64 = 20000;
+
+/// Decides whether or not a response should cause the request to be retried.
+pub type RetryCondition = Box<Fn(&HttpResponse) -> bool + Send + Sync>;
+
+/// Controls how many times, and how often, a failed request is retried.
+///
+/// The delay before retry `n` (starting at zero) is `base_delay * 2^n`, capped at `max_delay`.
+/// With jitter enabled a random delay between zero and that value is used instead, which keeps
+/// many clients that were throttled at the same time from retrying in lockstep.
+pub struct RetryPolicy {
+    max_attempts: u32,
+    base_delay: Duration,
+    max_delay: Duration,
+    jitter: bool,
+    deadline: Option<Duration>,
+    retry_condition: RetryCondition,
+}
+
+impl Default for RetryPolicy {
+    /// Three attempts with jittered backoff starting at 100ms, retrying throttling errors and
+    /// 500, 502, 503 and 504 responses.
+    fn default() -> RetryPolicy {
+        RetryPolicy {
+            max_attempts: DEFAULT_MAX_ATTEMPTS,
+            base_delay: Duration::from_millis(DEFAULT_BASE_DELAY_MS),
+            max_delay: Duration::from_millis(DEFAULT_MAX_DELAY_MS),
+            jitter: true,
+            deadline: None,
+            retry_condition: Box::new(is_retryable_response),
+        }
+    }
+}
+
+impl fmt::Debug for RetryPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("RetryPolicy")
+            .field("max_attempts", &self.max_attempts)
+            .field("base_delay", &self.base_delay)
+            .field("max_delay", &self.max_delay)
+            .field("jitter", &self.jitter)
+            .field("deadline", &self.deadline)
+            .finish()
+    }
+}
+
+impl RetryPolicy {
+    /// A policy that sends each request exactly once.
+    pub fn none() -> RetryPolicy {
+        RetryPolicy::default().with_max_attempts(1)
+    }
+
+    /// Set the total number of attempts, including the first one.  Values below one are
+    /// treated as one.
+    pub fn with_max_attempts(mut self, max_attempts: u32) -> RetryPolicy {
+        self.max_attempts = cmp::max(max_attempts, 1);
+        self
+    }
+
+    /// Set the delay used as the starting point for exponential backoff.
+    pub fn with_base_delay(mut self, base_delay: Duration) -> RetryPolicy {
+        self.base_delay = base_delay;
+        self
+    }
+
+    /// Set the upper bound for the delay between two attempts.
+    pub fn with_max_delay(mut self, max_delay: Duration) -> RetryPolicy {
+        self.max_delay = max_delay;
+        self
+    }
+
+    /// Enable or disable randomized ("full") jitter on the backoff delay.
+    pub fn with_jitter(mut self, jitter: bool) -> RetryPolicy {
+        self.jitter = jitter;
+        self
+    }
+
+    /// Give every call `deadline` to finish in, counted from the start of its first attempt.
+    ///
+    /// No retry is started that would begin after the deadline, and the connects, reads and
+    /// writes of an attempt that is under way give up when it passes, failing with a timeout.
+    /// The body of a streamed response is read after the call returns, so it isn't bounded.
+    pub fn with_deadline(mut self, deadline: Duration) -> RetryPolicy {
+        self.deadline = Some(deadline);
+        self
+    }
+
+    /// Replace the predicate that decides which responses are retried.
+    ///
+    /// `is_retryable_response` is the default and can be called from a custom predicate to
+    /// extend it rather than replace it.
+    pub fn with_retry_condition<F>(mut self, condition: F) -> RetryPolicy
+        where F: Fn(&HttpResponse) -> bool + Send + Sync + 'static
+    {
+        self.retry_condition = Box::new(condition);
+        self
+    }
+
+    /// Get the total number of attempts, including the first one.
+    pub fn max_attempts(&self) -> u32 {
+        self.max_attempts
+    }
+
+    /// The number of attempts allowed for `request`.  A streamed payload is consumed by the first
+    /// attempt, so requests with one are only sent once.
+    fn attempts_for(&self, request: &SignedRequest) -> u32 {
+        if request.payload_stream().is_some() { 1 } else { self.max_attempts }
+    }
+
+    /// The deadline of a call starting now, if the policy gives calls one.
+    pub fn call_deadline(&self) -> Option<Instant> {
+        self.deadline.map(|deadline| Instant::now() + deadline)
+    }
+
+    /// Whether or not the given response should be retried according to this policy.
+    pub fn should_retry(&self, response: &HttpResponse) -> bool {
+        (self.retry_condition)(response)
+    }
+
+    /// The delay to wait before retry number `retry` (zero based), without jitter applied.
+    pub fn backoff(&self, retry: u32) -> Duration {
+        // shifting by 32 or more would overflow, and the cap is reached long before that anyway
+        let factor = 1u32 << cmp::min(retry, 31);
+        let base_ms = duration_to_millis(self.base_delay);
+        let max_ms = duration_to_millis(self.max_delay);
+
+        Duration::from_millis(cmp::min(base_ms.saturating_mul(factor as u64), max_ms))
+    }
+
+    /// The delay to actually wait before retry number `retry`, with jitter applied if enabled.
+    pub fn delay(&self, retry: u32) -> Duration {
+        let backoff_ms = duration_to_millis(self.backoff(retry));
+
+        if !self.jitter || backoff_ms == 0 {
+            return Duration::from_millis(backoff_ms);
+        }
+
+        Duration::from_millis(rand::thread_rng().gen_range(0, backoff_ms + 1))
+    }
+
+    /// Sign the request with the given credentials and send it, re-signing and re-sending it
+    /// as long as the response is retryable and attempts remain.
+    ///
+    /// Errors dispatching the request are retried as well when `HttpDispatchError::is_retryable`
+    /// says it is safe to.  Once attempts are exhausted the last response or error is returned as-is.
+    /// The `before_signing` hooks of `interceptors` run once, the others for every attempt.
+    pub fn sign_and_dispatch<D>(&self, dispatcher: &D, interceptors: &InterceptorChain, request: &mut SignedRequest, creds: &AwsCredentials)
+        -> Result<HttpResponse, HttpDispatchError> where D: DispatchSignedRequest
+    {
+        if request.deadline().is_none() {
+            request.set_deadline(self.call_deadline());
+        }
+        if let Some(result) = interceptors.before_signing(request) {
+            return result;
+        }
+        self.resend(dispatcher, interceptors, request, creds)
+    }
+
+    /// Like `sign_and_dispatch`, but without running the `before_signing` hooks.
+    ///
+    /// Use this to send a request that has already been through them again, e.g. after a redirect.
+    pub fn resend<D>(&self, dispatcher: &D, interceptors: &InterceptorChain, request: &mut SignedRequest, creds: &AwsCredentials)
+        -> Result<HttpResponse, HttpDispatchError> where D: DispatchSignedRequest
+    {
+        let max_attempts = self.attempts_for(request);
+        let mut retry = 0;
+
+        loop {
+            // signing again refreshes x-amz-date, so retries aren't rejected as stale
+            let result = interceptors.send_attempt(dispatcher, request, creds);
+
+            let retryable = match result {
+                Ok(ref response) => self.should_retry(response),
+                Err(ref err) => err.is_retryable(),
+            };
+
+            if !retryable || retry + 1 >= max_attempts {
+                return result;
+            }
+
+            let delay = self.delay(retry);
+            if past_deadline(request.deadline(), delay) {
+                debug!("Not retrying request, its deadline would pass first");
+                return result;
+            }
+            match result {
+                Ok(ref response) => debug!("Retrying request after status {}, waiting {:?}", response.status, delay),
+                Err(ref err) => debug!("Retrying request after dispatch error \"{}\", waiting {:?}", err, delay),
+            }
+
+            thread::sleep(delay);
+            retry += 1;
+        }
+    }
+
+    /// Like `sign_and_dispatch`, but leaves the body of a successful response unread.
+    ///
+    /// Only error responses (status 400 and above) are buffered and checked against the retry
+    /// condition; those bodies are small and are needed to find the error code anyway.
+    pub fn sign_and_dispatch_streaming<D>(&self, dispatcher: &D, interceptors: &InterceptorChain, request: &mut SignedRequest, creds: &AwsCredentials)
+        -> Result<HttpStreamingResponse, HttpDispatchError> where D: DispatchSignedRequest
+    {
+        if request.deadline().is_none() {
+            request.set_deadline(self.call_deadline());
+        }
+        if let Some(result) = interceptors.before_signing(request) {
+            return result.map(HttpStreamingResponse::from);
+        }
+        self.resend_streaming(dispatcher, interceptors, request, creds)
+    }
+
+    /// Like `resend`, but leaves the body of a successful response unread.
+    pub fn resend_streaming<D>(&self, dispatcher: &D, interceptors: &InterceptorChain, request: &mut SignedRequest, creds: &AwsCredentials)
+        -> Result<HttpStreamingResponse, HttpDispatchError> where D: DispatchSignedRequest
+    {
+        let max_attempts = self.attempts_for(request);
+        let mut retry = 0;
+
+        loop {
+            let last_attempt = retry + 1 >= max_attempts;
+
+            let response = match interceptors.send_attempt_streaming(dispatcher, request, creds) {
+                Ok(response) => response,
+                Err(err) => {
+                    let delay = self.delay(retry);
+                    if last_attempt || !err.is_retryable() || past_deadline(request.deadline(), delay) {
+                        return Err(err);
+                    }
+                    debug!("Retrying request after dispatch error \"{}\", waiting {:?}", err, delay);
+                    thread::sleep(delay);
+                    retry += 1;
+                    continue;
+                }
+            };
+
+            if response.status < 400 || last_attempt {
+                return Ok(response);
+            }
+
+            let buffered = try!(response.buffer());
+            let delay = self.delay(retry);
+            if !self.should_retry(&buffered) || past_deadline(request.deadline(), delay) {
+                return Ok(HttpStreamingResponse::from(buffered));
+            }
+
+            debug!("Retrying request after status {}, waiting {:?}", buffered.status, delay);
+            thread::sleep(delay);
+            retry += 1;
+        }
+    }
+}
+
+/// Whether waiting `delay` before another attempt would leave it starting after `deadline`.
+pub fn past_deadline(deadline: Option<Instant>, delay: Duration) -> bool {
+    deadline.map_or(false, |deadline| Instant::now() + delay >= deadline)
+}
+
+/// The default retry condition: throttling errors and 500, 502, 503 and 504 responses.
+pub fn is_retryable_response(response: &HttpResponse) -> bool {
+    if RETRYABLE_STATUS_CODES.contains(&response.status) {
+        return true;
+    }
+
+    if response.status < 400 {
+        return false;
+    }
+
+    match error_code(&String::from_utf8_lossy(&response.body)) {
+        Some(ref code) => is_throttling_code(code),
+        None => false,
+    }
+}
+
+/// Whether `code` is one of the error codes AWS services use to signal throttling.
+pub fn is_throttling_code(code: &str) -> bool {
+    THROTTLING_ERROR_CODES.contains(&code)
+}
+
+/// Extract the AWS error code from a JSON (`__type`) or XML (`<Code>`) error body.
+pub fn error_code(body: &str) -> Option<String> {
+    lazy_static! {
+        static ref XML_CODE_RE: Regex = Regex::new(r"<Code>([^<]+)</Code>").unwrap();
+    }
+
+    if let Ok(json) = from_str::<Value>(body) {
+        // JSON protocols may prefix the code with a namespace, as in
+        // "com.amazonaws.dynamodb.v20120810#ProvisionedThroughputExceededException"
+        return json.find("__type")
+            .or_else(|| json.find("code"))
+            .and_then(|code| code.as_str())
+            .map(|code| code.rsplit('#').next().unwrap_or(code).to_owned());
+    }
+
+    XML_CODE_RE.captures(body)
+        .and_then(|caps| caps.at(1))
+        .map(|code| code.trim().to_owned())
+}
+
+fn duration_to_millis(duration: Duration) -> u64 {
+    duration.as_secs()
+        .saturating_mul(1000)
+        .saturating_add((duration.subsec_nanos() / 1000000) as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+    use std::io::Cursor;
+    use std::time::Duration;
+
+    use chrono::{Duration as ChronoDuration, UTC};
+
+    use credential::AwsCredentials;
+    use interceptor::{Interceptor, InterceptorChain};
+    use mock::{RequestMatcher, ScriptedDispatcher};
+    use region::Region;
+    use request::{DispatchSignedRequest, HttpDispatchError, HttpDispatchErrorKind, HttpResponse};
+    use signature::SignedRequest;
+    use super::*;
+
+    struct SequenceDispatcher {
+        responses: RefCell<Vec<HttpResponse>>,
+        calls: RefCell<u32>,
+    }
+
+    impl SequenceDispatcher {
+        fn new(statuses: &[u16]) -> SequenceDispatcher {
+            let mut responses: Vec<HttpResponse> = statuses.iter().map(|status| {
+                let mut response = HttpResponse::default();
+                response.status = *status;
+                response
+            }).collect();
+            responses.reverse();
+
+            SequenceDispatcher {
+                responses: RefCell::new(responses),
+                calls: RefCell::new(0),
+            }
+        }
+    }
+
+    impl DispatchSignedRequest for SequenceDispatcher {
+        fn dispatch(&self, _request: &SignedRequest) -> Result<HttpResponse, HttpDispatchError> {
+            *self.calls.borrow_mut() += 1;
+            Ok(self.responses.borrow_mut().pop().expect("no more responses"))
+        }
+    }
+
+    fn response_with(status: u16, body: &str) -> HttpResponse {
+        let mut response = HttpResponse::default();
+        response.status = status;
+        response.body = body.as_bytes().to_vec();
+        response
+    }
+
+    fn no_delay_policy(max_attempts: u32) -> RetryPolicy {
+        RetryPolicy::default()
+            .with_max_attempts(max_attempts)
+            .with_base_delay(Duration::from_millis(0))
+    }
+
+    fn credentials() -> AwsCredentials {
+        AwsCredentials::new("key", "secret", None, UTC::now() + ChronoDuration::seconds(600))
+    }
+
+    #[test]
+    fn backoff_grows_exponentially_up_to_the_cap() {
+        let policy = RetryPolicy::default()
+            .with_base_delay(Duration::from_millis(100))
+            .with_max_delay(Duration::from_millis(1000));
+
+        assert_eq!(policy.backoff(0), Duration::from_millis(100));
+        assert_eq!(policy.backoff(1), Duration::from_millis(200));
+        assert_eq!(policy.backoff(3), Duration::from_millis(800));
+        assert_eq!(policy.backoff(4), Duration::from_millis(1000));
+        assert_eq!(policy.backoff(100), Duration::from_millis(1000));
+    }
+
+    #[test]
+    fn jittered_delay_never_exceeds_backoff() {
+        let policy = RetryPolicy::default().with_base_delay(Duration::from_millis(50));
+        for retry in 0..10 {
+            assert!(policy.delay(retry) <= policy.backoff(retry));
+        }
+    }
+
+    #[test]
+    fn error_code_from_json_and_xml() {
+        assert_eq!(error_code("{\"__type\":\"com.amazonaws.dynamodb.v20120810#ProvisionedThroughputExceededException\",\"message\":\"slow down\"}"),
+            Some("ProvisionedThroughputExceededException".to_owned()));
+        assert_eq!(error_code("<ErrorResponse><Error><Type>Sender</Type><Code>Throttling</Code><Message>Rate exceeded</Message></Error></ErrorResponse>"),
+            Some("Throttling".to_owned()));
+        assert_eq!(error_code("not an error body"), None);
+    }
+
+    #[test]
+    fn default_condition() {
+        assert!(is_retryable_response(&response_with(503, "")));
+        assert!(is_retryable_response(&response_with(400, "{\"__type\":\"ThrottlingException\"}")));
+        assert!(is_retryable_response(&response_with(503, "<Response><Errors><Error><Code>RequestLimitExceeded</Code></Error></Errors></Response>")));
+        assert!(!is_retryable_response(&response_with(400, "{\"__type\":\"ValidationException\"}")));
+        assert!(!is_retryable_response(&response_with(200, "")));
+    }
+
+    #[test]
+    fn retries_until_success() {
+        let dispatcher = SequenceDispatcher::new(&[503, 500, 200]);
+        let mut request = SignedRequest::new("POST", "sqs", Region::UsEast1, "/");
+
+        let response = no_delay_policy(3).sign_and_dispatch(&dispatcher, &InterceptorChain::new(), &mut request, &credentials()).unwrap();
+
+        assert_eq!(response.status, 200);
+        assert_eq!(*dispatcher.calls.borrow(), 3);
+    }
+
+    #[test]
+    fn stops_after_max_attempts() {
+        let dispatcher = SequenceDispatcher::new(&[503, 503, 503]);
+        let mut request = SignedRequest::new("POST", "sqs", Region::UsEast1, "/");
+
+        let response = no_delay_policy(2).sign_and_dispatch(&dispatcher, &InterceptorChain::new(), &mut request, &credentials()).unwrap();
+
+        assert_eq!(response.status, 503);
+        assert_eq!(*dispatcher.calls.borrow(), 2);
+    }
+
+    struct TraceId;
+
+    impl Interceptor for TraceId {
+        fn before_signing(&self, request: &mut SignedRequest) -> Option<Result<HttpResponse, HttpDispatchError>> {
+            request.add_header("x-trace-id", "abc123");
+            None
+        }
+    }
+
+    #[test]
+    fn before_signing_runs_once_per_call() {
+        let dispatcher = SequenceDispatcher::new(&[503, 500, 200]);
+        let mut request = SignedRequest::new("POST", "sqs", Region::UsEast1, "/");
+        let mut interceptors = InterceptorChain::new();
+        interceptors.push(TraceId);
+
+        let response = no_delay_policy(3).sign_and_dispatch(&dispatcher, &interceptors, &mut request, &credentials()).unwrap();
+
+        assert_eq!(response.status, 200);
+        assert_eq!(*dispatcher.calls.borrow(), 3);
+        assert_eq!(request.headers()["x-trace-id"], vec![b"abc123".to_vec()]);
+    }
+
+    #[test]
+    fn does_not_retry_client_errors() {
+        let dispatcher = SequenceDispatcher::new(&[400, 200]);
+        let mut request = SignedRequest::new("POST", "sqs", Region::UsEast1, "/");
+
+        let response = no_delay_policy(3).sign_and_dispatch(&dispatcher, &InterceptorChain::new(), &mut request, &credentials()).unwrap();
+
+        assert_eq!(response.status, 400);
+        assert_eq!(*dispatcher.calls.borrow(), 1);
+    }
+
+    #[test]
+    fn custom_condition() {
+        let dispatcher = SequenceDispatcher::new(&[409, 200]);
+        let mut request = SignedRequest::new("POST", "sqs", Region::UsEast1, "/");
+        let policy = no_delay_policy(3).with_retry_condition(|response| response.status == 409);
+
+        let response = policy.sign_and_dispatch(&dispatcher, &InterceptorChain::new(), &mut request, &credentials()).unwrap();
+
+        assert_eq!(response.status, 200);
+        assert_eq!(*dispatcher.calls.borrow(), 2);
+    }
+
+    #[test]
+    fn streaming_retries_error_responses() {
+        let dispatcher = SequenceDispatcher::new(&[503, 200]);
+        let mut request = SignedRequest::new("GET", "s3", Region::UsEast1, "/");
+
+        let response = no_delay_policy(3).sign_and_dispatch_streaming(&dispatcher, &InterceptorChain::new(), &mut request, &credentials()).unwrap();
+
+        assert_eq!(response.status, 200);
+        assert_eq!(*dispatcher.calls.borrow(), 2);
+    }
+
+    #[test]
+    fn streamed_payloads_are_not_retried() {
+        let dispatcher = SequenceDispatcher::new(&[503, 200]);
+        let mut request = SignedRequest::new("PUT", "s3", Region::UsEast1, "/");
+        request.set_payload_stream(Cursor::new(vec![0u8; 16]), 16);
+
+        let response = no_delay_policy(3).sign_and_dispatch(&dispatcher, &InterceptorChain::new(), &mut request, &credentials()).unwrap();
+
+        assert_eq!(response.status, 503);
+        assert_eq!(*dispatcher.calls.borrow(), 1);
+    }
+
+    #[test]
+    fn deadline_bounds_retries() {
+        let dispatcher = SequenceDispatcher::new(&[503, 503, 200]);
+        let mut request = SignedRequest::new("POST", "sqs", Region::UsEast1, "/");
+        let policy = RetryPolicy::default()
+            .with_jitter(false)
+            .with_base_delay(Duration::from_millis(50))
+            .with_deadline(Duration::from_millis(100));
+
+        let response = policy.sign_and_dispatch(&dispatcher, &InterceptorChain::new(), &mut request, &credentials()).unwrap();
+
+        // the first retry waits 50ms, the second would have to wait until 150ms
+        assert_eq!(response.status, 503);
+        assert_eq!(*dispatcher.calls.borrow(), 2);
+
+        // and every attempt was sent with the deadline, to bound it as well
+        assert!(request.deadline().is_some());
+    }
+
+    #[test]
+    fn dispatch_errors_are_retried_unless_the_request_is_invalid() {
+        let mut request = SignedRequest::new("POST", "sqs", Region::UsEast1, "/");
+
+        let dispatcher = ScriptedDispatcher::new()
+            .fail(RequestMatcher::any(), HttpDispatchError::new(HttpDispatchErrorKind::ConnectionReset, "reset"))
+            .respond_with(RequestMatcher::any(), 200, "");
+        let response = no_delay_policy(3).sign_and_dispatch(&dispatcher, &InterceptorChain::new(), &mut request, &credentials()).unwrap();
+        assert_eq!(response.status, 200);
+
+        let dispatcher = ScriptedDispatcher::new()
+            .fail(RequestMatcher::any(), HttpDispatchError::new(HttpDispatchErrorKind::InvalidRequest, "bad verb"));
+        let err = no_delay_policy(3).sign_and_dispatch(&dispatcher, &InterceptorChain::new(), &mut request, &credentials()).unwrap_err();
+        assert_eq!(err.kind(), HttpDispatchErrorKind::InvalidRequest);
+        dispatcher.assert_calls(1);
+    }
+}
@@ -8,12 +8,15 @@ use std::ascii::AsciiExt;
 use std::collections::HashMap;
 use std::error::Error;
 use std::io::BufReader;
+use std::io;
 use std::io::Read;
+use std::mem;
 use std::num::ParseIntError;
 use std::str::{FromStr, ParseBoolError};
 use std::str;
+use std::time::Duration;
 
-use hyper::client::{Client, RedirectPolicy};
+use hyper::client::Client;
 use md5;
 use rustc_serialize::base64::{ToBase64, STANDARD};
 use xml::*;
@@ -22,19 +25,46 @@ use regex::Regex;
 use credential::{ProvideAwsCredentials, AwsCredentials, CredentialsError};
 use param::{Params, ServiceParams};
 use region::Region;
-use signature::SignedRequest;
+use signature::{PayloadSigning, SignedRequest, build_hostname};
 use xmlutil::*;
-use request::{DispatchSignedRequest, HttpResponse, HttpDispatchError};
+use request::{default_client, DispatchSignedRequest, HttpResponse, HttpStreamingResponse, HttpDispatchError};
 use region;
+use endpoint::Endpoint;
+use error::AwsError;
+use interceptor::{Interceptor, InterceptorChain};
+use metadata::ResponseMetadata;
+use post_policy::PostPolicy;
+use retry::{RetryPolicy, error_code};
 
 #[derive(Debug, Default)]
 pub struct S3Error {
-    pub message: String
+    pub message: String,
+    /// The error code S3 sent, such as `NoSuchKey`, if the error was read from a response.
+    pub code: Option<String>,
+    /// The metadata of the response the error was read from, if there was one.
+    pub metadata: Option<ResponseMetadata>,
+    dispatch_error: Option<HttpDispatchError>,
 }
 
 impl S3Error {
     fn new<S>(message: S) -> S3Error where S: Into<String> {
-        S3Error { message: message.into() }
+        S3Error { message: message.into(), ..S3Error::default() }
+    }
+
+    /// An error for a response with an unexpected status.
+    fn from_response(response: &HttpResponse, metadata: ResponseMetadata) -> S3Error {
+        S3Error::new(format!("Unexpected HTTP status code {}", metadata.status)).with_response(response, metadata)
+    }
+
+    fn with_metadata(mut self, metadata: ResponseMetadata) -> S3Error {
+        self.metadata = Some(metadata);
+        self
+    }
+
+    /// Keep the metadata of an error response, and the code in its body.
+    fn with_response(mut self, response: &HttpResponse, metadata: ResponseMetadata) -> S3Error {
+        self.code = error_code(&String::from_utf8_lossy(&response.body));
+        self.with_metadata(metadata)
     }
 }
 
@@ -47,36 +77,55 @@ impl Error for S3Error {
     fn description(&self) -> &str {
         &self.message
     }
+
+    fn cause(&self) -> Option<&Error> {
+        self.dispatch_error.as_ref().map(|dispatch_error| dispatch_error as &Error)
+    }
+}
+
+impl AwsError for S3Error {
+    fn code(&self) -> Option<&str> {
+        self.code.as_ref().map(|code| &code[..])
+    }
+
+    fn status(&self) -> Option<u16> {
+        self.metadata.as_ref().map(|metadata| metadata.status)
+    }
+
+    fn http_dispatch_error(&self) -> Option<&HttpDispatchError> {
+        self.dispatch_error.as_ref()
+    }
 }
 
 impl From<HttpDispatchError> for S3Error {
     fn from(err: HttpDispatchError) -> S3Error {
-        S3Error { message: err.description().to_owned() }
+        let message = err.description().to_owned();
+        S3Error { dispatch_error: Some(err), ..S3Error::new(message) }
     }
 }
 
 impl From<CredentialsError> for S3Error {
     fn from(err: CredentialsError) -> S3Error {
-        S3Error { message: err.description().to_owned() }
+        S3Error::new(err.description())
     }
 }
 
 impl From<ParseIntError> for S3Error {
     fn from(err: ParseIntError) -> S3Error {
-        S3Error { message: err.description().to_owned() }
+        S3Error::new(err.description())
     }
 }
 
 impl From<ParseBoolError> for S3Error {
     fn from(err: ParseBoolError) -> S3Error {
-        S3Error { message: err.description().to_owned() }
+        S3Error::new(err.description())
     }
 }
 
 impl From<XmlParseError> for S3Error {
     fn from(err: XmlParseError) -> S3Error {
         let XmlParseError(message) = err;
-        S3Error { message: message.to_owned() }
+        S3Error::new(message)
     }
 }
 
@@ -5738,13 +5787,13 @@ impl GetObjectTorrentOutputParser {
     }
 }
 
-pub type ContentLength = i32;
+pub type ContentLength = i64;
 /// Parse `ContentLength` from XML
 struct ContentLengthParser;
 impl ContentLengthParser {
     fn parse_xml<T: Peek + Next>(tag_name: &str, stack: &mut T) -> Result<ContentLength, XmlParseError> {
         try!(start_element(tag_name, stack));
-        let obj = i32::from_str(try!(characters(stack)).as_ref()).unwrap();
+        let obj = i64::from_str(try!(characters(stack)).as_ref()).unwrap();
         try!(end_element(tag_name, stack));
         Ok(obj)
     }
@@ -5855,6 +5904,22 @@ impl QueueConfigurationDeprecatedWriter {
         EventWriter::write_params(params, &(prefix.to_string() + "Event"), &obj.event);
     }
 }
+/// The result of `get_object_streaming`.
+///
+/// Reading from it reads the object's contents straight from the connection.
+pub struct GetObjectStreamingOutput {
+    /// Everything S3 returned about the object.  Its `body` is always empty.
+    pub output: GetObjectOutput,
+    /// The object's contents.
+    pub body: Box<Read + Send>,
+}
+
+impl Read for GetObjectStreamingOutput {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.body.read(buf)
+    }
+}
+
 #[derive(Debug, Default)]
 pub struct GetObjectOutput {
     /// Last modified date of the object
@@ -6389,6 +6454,9 @@ pub struct PutObjectRequest<'a> {
     /// Amazon S3 uses this header for a message integrity check to ensure the
     /// encryption key was transmitted without error.
     pub sse_customer_key_md5: Option<SSECustomerKeyMD5>,
+    /// How `body` is covered by the signature.  Leaving it unsigned saves hashing large
+    /// objects sent over HTTPS.
+    pub payload_signing: PayloadSigning,
 }
 
 pub type Code = String;
@@ -8387,6 +8455,9 @@ pub struct UploadPartRequest <'a> {
     /// Part number of part being uploaded. This is a positive integer between 1 and
     /// 10,000.
     pub part_number: PartNumber,
+    /// How `body` is covered by the signature.  Leaving it unsigned saves hashing large
+    /// parts sent over HTTPS.
+    pub payload_signing: PayloadSigning,
 }
 
 
@@ -9156,14 +9227,15 @@ impl MaxPartsWriter {
 pub struct S3Client<P, D> where P: ProvideAwsCredentials, D: DispatchSignedRequest {
             credentials_provider: P,
             region: region::Region,
+            endpoint: Option<Endpoint>,
             dispatcher: D,
+            retry_policy: RetryPolicy,
+            interceptors: InterceptorChain,
         }
 
 impl<P> S3Client<P, Client> where P: ProvideAwsCredentials {
     pub fn new(credentials_provider: P, region: region::Region) -> Self {
-        let mut client = Client::new();
-        client.set_redirect_policy(RedirectPolicy::FollowNone);
-        S3Client::with_request_dispatcher(client, credentials_provider, region)
+        S3Client::with_request_dispatcher(default_client(), credentials_provider, region)
     }
 }
 
@@ -9172,62 +9244,102 @@ impl<P, D> S3Client<P, D> where P: ProvideAwsCredentials, D: DispatchSignedReque
         S3Client {
             credentials_provider: credentials_provider,
             region: region,
-            dispatcher: request_dispatcher
+            endpoint: None,
+            dispatcher: request_dispatcher,
+            retry_policy: RetryPolicy::default(),
+            interceptors: InterceptorChain::new(),
         }
     }
 
+    /// Send requests to `endpoint` instead of the default endpoint for the region.
+    ///
+    /// Buckets are always addressed path style (`/bucket/key`) on a custom endpoint.
+    pub fn set_endpoint(&mut self, endpoint: Endpoint) {
+        self.endpoint = Some(endpoint);
+    }
+
+    /// Set the policy used to retry throttled requests and transient server errors.
+    pub fn set_retry_policy(&mut self, retry_policy: RetryPolicy) {
+        self.retry_policy = retry_policy;
+    }
+
+    /// Run every request made by this client through `interceptor`, after those added before it.
+    pub fn add_interceptor<I>(&mut self, interceptor: I) where I: Interceptor + 'static {
+        self.interceptors.push(interceptor);
+    }
+
     /// Returns metadata about all of the versions of objects in a bucket.
     pub fn list_object_versions(&self, input: &ListObjectVersionsRequest) -> Result<ListObjectVersionsOutput, S3Error> {
-        let mut request = SignedRequest::new("GET", "s3", self.region, "/{Bucket}?versions");
+        self.list_object_versions_with_metadata(input).map(|(output, _)| output)
+    }
+
+    /// Like `list_object_versions`, but also returns the metadata of the response.
+    pub fn list_object_versions_with_metadata(&self, input: &ListObjectVersionsRequest) -> Result<(ListObjectVersionsOutput, ResponseMetadata), S3Error> {
+        let mut request = SignedRequest::new("GET", "s3", self.region.clone(), "/{Bucket}?versions");
+        request.set_endpoint(self.endpoint.clone());
         let mut params = Params::new();
         params.put("Action", "ListObjectVersions");
         ListObjectVersionsRequestWriter::write_params(&mut params, "", input);
         request.set_params(params);
-        let result = try!(sign_and_execute(&self.dispatcher, &mut request, try!(self.credentials_provider.credentials())));
+        let result = try!(sign_and_execute(&self.dispatcher, &mut request, try!(self.credentials_provider.credentials()), &self.retry_policy, &self.interceptors));
+        let metadata = ResponseMetadata::from_response(&result);
         let status = result.status;
-        let mut reader = EventReader::from_str(&result.body);
+        let mut reader = EventReader::new(&result.body[..]);
         let mut stack = XmlResponse::new(reader.events().peekable());
         stack.next(); // xml start tag
         stack.next();
         match status {
             200 => {
-                Ok(try!(ListObjectVersionsOutputParser::parse_xml("ListObjectVersionsOutput", &mut stack)))
+                Ok((try!(ListObjectVersionsOutputParser::parse_xml("ListObjectVersionsOutput", &mut stack)), metadata))
             }
-            _ => { Err(S3Error::new(format!("Unexpected HTTP status code {}", status))) }
+            _ => { Err(S3Error::from_response(&result, metadata)) }
         }
     }
     /// Replaces a policy on a bucket. If the bucket already has a policy, the one in
     /// this request completely replaces it.
     pub fn put_bucket_policy(&self, input: &PutBucketPolicyRequest) -> Result<(), S3Error> {
-        let mut request = SignedRequest::new("PUT", "s3", self.region, "/{Bucket}?policy");
+        self.put_bucket_policy_with_metadata(input).map(|(output, _)| output)
+    }
+
+    /// Like `put_bucket_policy`, but also returns the metadata of the response.
+    pub fn put_bucket_policy_with_metadata(&self, input: &PutBucketPolicyRequest) -> Result<((), ResponseMetadata), S3Error> {
+        let mut request = SignedRequest::new("PUT", "s3", self.region.clone(), "/{Bucket}?policy");
+        request.set_endpoint(self.endpoint.clone());
         let mut params = Params::new();
         params.put("Action", "PutBucketPolicy");
         PutBucketPolicyRequestWriter::write_params(&mut params, "", input);
         request.set_params(params);
-        let result = try!(sign_and_execute(&self.dispatcher, &mut request, try!(self.credentials_provider.credentials())));
+        let result = try!(sign_and_execute(&self.dispatcher, &mut request, try!(self.credentials_provider.credentials()), &self.retry_policy, &self.interceptors));
+        let metadata = ResponseMetadata::from_response(&result);
         let status = result.status;
-        let mut reader = EventReader::from_str(&result.body);
+        let mut reader = EventReader::new(&result.body[..]);
         let mut stack = XmlResponse::new(reader.events().peekable());
         stack.next(); // xml start tag
         stack.next();
         match status {
             200 => {
-                Ok(())
+                Ok(((), metadata))
             }
-            _ => { Err(S3Error::new(format!("Unexpected HTTP status code {}", status))) }
+            _ => { Err(S3Error::from_response(&result, metadata)) }
         }
     }
     /// Returns some or all (up to 1000) of the objects in a bucket. You can use the
     /// request parameters as selection criteria to return a subset of the objects in
     /// a bucket.
     pub fn list_objects(&self, input: &ListObjectsRequest) -> Result<ListObjectsOutput, S3Error> {
+        self.list_objects_with_metadata(input).map(|(output, _)| output)
+    }
+
+    /// Like `list_objects`, but also returns the metadata of the response.
+    pub fn list_objects_with_metadata(&self, input: &ListObjectsRequest) -> Result<(ListObjectsOutput, ResponseMetadata), S3Error> {
         let mut path = String::from("/");
-        if !is_dns_compatible(&input.bucket) {
+        if !self.use_virtual_hosting(&input.bucket) {
             path = format!("{}{}/", path, &input.bucket);
         }
-        let mut request = SignedRequest::new("GET", "s3", self.region, &path);
+        let mut request = SignedRequest::new("GET", "s3", self.region.clone(), &path);
+        request.set_endpoint(self.endpoint.clone());
 
-        if is_dns_compatible(&input.bucket) {
+        if self.use_virtual_hosting(&input.bucket) {
             let hostname = self.hostname(Some(&input.bucket));
             request.set_hostname(Some(hostname));
         }
@@ -9235,114 +9347,181 @@ impl<P, D> S3Client<P, D> where P: ProvideAwsCredentials, D: DispatchSignedReque
 
         ListObjectsRequestWriter::write_params(&mut params, "", input);
         request.set_params(params);
-        let result = try!(sign_and_execute(&self.dispatcher, &mut request, try!(self.credentials_provider.credentials())));
+        let result = try!(sign_and_execute(&self.dispatcher, &mut request, try!(self.credentials_provider.credentials()), &self.retry_policy, &self.interceptors));
+        let metadata = ResponseMetadata::from_response(&result);
         let status = result.status;
-        let mut reader = EventReader::from_str(&result.body);
+        let mut reader = EventReader::new(&result.body[..]);
         let mut stack = XmlResponse::new(reader.events().peekable());
         stack.next(); // xml start tag
         match status {
             200 => {
-                Ok(try!(ListObjectsOutputParser::parse_xml("ListBucketResult", &mut stack)))
+                Ok((try!(ListObjectsOutputParser::parse_xml("ListBucketResult", &mut stack)), metadata))
             }
-            _ => { Err(S3Error::new(format!("Unexpected HTTP status code {}", status))) }
+            _ => { Err(S3Error::from_response(&result, metadata)) }
         }
     }
     /// Set the website configuration for a bucket.
     pub fn put_bucket_website(&self, input: &PutBucketWebsiteRequest) -> Result<(), S3Error> {
-        let mut request = SignedRequest::new("PUT", "s3", self.region, "/{Bucket}?website");
+        self.put_bucket_website_with_metadata(input).map(|(output, _)| output)
+    }
+
+    /// Like `put_bucket_website`, but also returns the metadata of the response.
+    pub fn put_bucket_website_with_metadata(&self, input: &PutBucketWebsiteRequest) -> Result<((), ResponseMetadata), S3Error> {
+        let mut request = SignedRequest::new("PUT", "s3", self.region.clone(), "/{Bucket}?website");
+        request.set_endpoint(self.endpoint.clone());
         let mut params = Params::new();
         params.put("Action", "PutBucketWebsite");
         PutBucketWebsiteRequestWriter::write_params(&mut params, "", input);
         request.set_params(params);
-        let result = try!(sign_and_execute(&self.dispatcher, &mut request, try!(self.credentials_provider.credentials())));
+        let result = try!(sign_and_execute(&self.dispatcher, &mut request, try!(self.credentials_provider.credentials()), &self.retry_policy, &self.interceptors));
+        let metadata = ResponseMetadata::from_response(&result);
         let status = result.status;
-        let mut reader = EventReader::from_str(&result.body);
+        let mut reader = EventReader::new(&result.body[..]);
         let mut stack = XmlResponse::new(reader.events().peekable());
         stack.next(); // xml start tag
         stack.next();
         match status {
             200 => {
-                Ok(())
+                Ok(((), metadata))
             }
-            _ => { Err(S3Error::new(format!("Unexpected HTTP status code {}", status))) }
+            _ => { Err(S3Error::from_response(&result, metadata)) }
         }
     }
     /// Deprecated, see the PutBucketNotificationConfiguraiton operation.
     pub fn put_bucket_notification(&self, input: &PutBucketNotificationRequest) -> Result<(), S3Error> {
-        let mut request = SignedRequest::new("PUT", "s3", self.region, "/{Bucket}?notification");
+        self.put_bucket_notification_with_metadata(input).map(|(output, _)| output)
+    }
+
+    /// Like `put_bucket_notification`, but also returns the metadata of the response.
+    pub fn put_bucket_notification_with_metadata(&self, input: &PutBucketNotificationRequest) -> Result<((), ResponseMetadata), S3Error> {
+        let mut request = SignedRequest::new("PUT", "s3", self.region.clone(), "/{Bucket}?notification");
+        request.set_endpoint(self.endpoint.clone());
         let mut params = Params::new();
         params.put("Action", "PutBucketNotification");
         PutBucketNotificationRequestWriter::write_params(&mut params, "", input);
         request.set_params(params);
-        let result = try!(sign_and_execute(&self.dispatcher, &mut request, try!(self.credentials_provider.credentials())));
+        let result = try!(sign_and_execute(&self.dispatcher, &mut request, try!(self.credentials_provider.credentials()), &self.retry_policy, &self.interceptors));
+        let metadata = ResponseMetadata::from_response(&result);
         let status = result.status;
-        let mut reader = EventReader::from_str(&result.body);
+        let mut reader = EventReader::new(&result.body[..]);
         let mut stack = XmlResponse::new(reader.events().peekable());
         stack.next(); // xml start tag
         stack.next();
         match status {
             200 => {
-                Ok(())
+                Ok(((), metadata))
             }
-            _ => { Err(S3Error::new(format!("Unexpected HTTP status code {}", status))) }
+            _ => { Err(S3Error::from_response(&result, metadata)) }
         }
     }
     /// Set the logging parameters for a bucket and to specify permissions for who can
     /// view and modify the logging parameters. To set the logging status of a bucket,
     /// you must be the bucket owner.
     pub fn put_bucket_logging(&self, input: &PutBucketLoggingRequest) -> Result<(), S3Error> {
-        let mut request = SignedRequest::new("PUT", "s3", self.region, "/{Bucket}?logging");
+        self.put_bucket_logging_with_metadata(input).map(|(output, _)| output)
+    }
+
+    /// Like `put_bucket_logging`, but also returns the metadata of the response.
+    pub fn put_bucket_logging_with_metadata(&self, input: &PutBucketLoggingRequest) -> Result<((), ResponseMetadata), S3Error> {
+        let mut request = SignedRequest::new("PUT", "s3", self.region.clone(), "/{Bucket}?logging");
+        request.set_endpoint(self.endpoint.clone());
         let mut params = Params::new();
         params.put("Action", "PutBucketLogging");
         PutBucketLoggingRequestWriter::write_params(&mut params, "", input);
         request.set_params(params);
-        let result = try!(sign_and_execute(&self.dispatcher, &mut request, try!(self.credentials_provider.credentials())));
+        let result = try!(sign_and_execute(&self.dispatcher, &mut request, try!(self.credentials_provider.credentials()), &self.retry_policy, &self.interceptors));
+        let metadata = ResponseMetadata::from_response(&result);
         let status = result.status;
-        let mut reader = EventReader::from_str(&result.body);
+        let mut reader = EventReader::new(&result.body[..]);
         let mut stack = XmlResponse::new(reader.events().peekable());
         stack.next(); // xml start tag
         stack.next();
         match status {
             200 => {
-                Ok(())
+                Ok(((), metadata))
             }
-            _ => { Err(S3Error::new(format!("Unexpected HTTP status code {}", status))) }
+            _ => { Err(S3Error::from_response(&result, metadata)) }
         }
     }
     /// Creates a new replication configuration (or replaces an existing one, if
     /// present).
     pub fn put_bucket_replication(&self, input: &PutBucketReplicationRequest) -> Result<(), S3Error> {
-        let mut request = SignedRequest::new("PUT", "s3", self.region, "/{Bucket}?replication");
+        self.put_bucket_replication_with_metadata(input).map(|(output, _)| output)
+    }
+
+    /// Like `put_bucket_replication`, but also returns the metadata of the response.
+    pub fn put_bucket_replication_with_metadata(&self, input: &PutBucketReplicationRequest) -> Result<((), ResponseMetadata), S3Error> {
+        let mut request = SignedRequest::new("PUT", "s3", self.region.clone(), "/{Bucket}?replication");
+        request.set_endpoint(self.endpoint.clone());
         let mut params = Params::new();
         params.put("Action", "PutBucketReplication");
         PutBucketReplicationRequestWriter::write_params(&mut params, "", input);
         request.set_params(params);
-        let result = try!(sign_and_execute(&self.dispatcher, &mut request, try!(self.credentials_provider.credentials())));
+        let result = try!(sign_and_execute(&self.dispatcher, &mut request, try!(self.credentials_provider.credentials()), &self.retry_policy, &self.interceptors));
+        let metadata = ResponseMetadata::from_response(&result);
         let status = result.status;
-        let mut reader = EventReader::from_str(&result.body);
+        let mut reader = EventReader::new(&result.body[..]);
         let mut stack = XmlResponse::new(reader.events().peekable());
         stack.next(); // xml start tag
         stack.next();
         match status {
             200 => {
-                Ok(())
+                Ok(((), metadata))
             }
-            _ => { Err(S3Error::new(format!("Unexpected HTTP status code {}", status))) }
+            _ => { Err(S3Error::from_response(&result, metadata)) }
         }
     }
     /// Uploads a part in a multipart upload.
     /// **Note:** After you initiate multipart upload and upload one or more parts, you must either complete or abort multipart upload in order to stop getting charged for storage of the uploaded parts. Only after you either complete or abort multipart upload, Amazon S3 frees up the parts storage and stops charging you for the parts storage.
     pub fn upload_part(&self, input: &UploadPartRequest) -> Result<String, S3Error> {
+        self.upload_part_with_metadata(input).map(|(output, _)| output)
+    }
+
+    /// Like `upload_part`, but also returns the metadata of the response.
+    pub fn upload_part_with_metadata(&self, input: &UploadPartRequest) -> Result<(String, ResponseMetadata), S3Error> {
+        try!(input.payload_signing.check_digest().map_err(S3Error::new));
+        let mut request = self.upload_part_request(input);
+        request.set_payload(input.body);
+        request.set_payload_signing(input.payload_signing.clone());
+
+        let result = try!(sign_and_execute(&self.dispatcher, &mut request, try!(self.credentials_provider.credentials()), &self.retry_policy, &self.interceptors));
+        S3Client::<P,D>::upload_part_result(result)
+    }
+
+    /// Uploads a part in a multipart upload, reading its contents from `body` as they are sent.
+    ///
+    /// `body` must yield exactly `length` bytes.  `input.body` is ignored.  Since the contents
+    /// can only be read once, the request is not retried.
+    pub fn upload_part_streaming<R>(&self, input: &UploadPartRequest, body: R, length: u64) -> Result<String, S3Error> where R: Read + Send {
+        self.upload_part_streaming_with_metadata(input, body, length).map(|(output, _)| output)
+    }
+
+    /// Like `upload_part_streaming`, but also returns the metadata of the response.
+    pub fn upload_part_streaming_with_metadata<R>(&self, input: &UploadPartRequest, body: R, length: u64) -> Result<(String, ResponseMetadata), S3Error> where R: Read + Send {
+        let mut request = self.upload_part_request(input);
+        request.set_payload_stream(body, length);
+
+        let result = try!(sign_and_execute(&self.dispatcher, &mut request, try!(self.credentials_provider.credentials()), &self.retry_policy, &self.interceptors));
+        S3Client::<P,D>::upload_part_result(result)
+    }
+
+    /// A URL anyone can use to upload a part of a multipart upload until `expires_in` has
+    /// passed, without credentials of their own.  `input.body` is ignored.
+    pub fn presigned_upload_part_url(&self, input: &UploadPartRequest, expires_in: Duration) -> Result<String, S3Error> {
+        let mut request = self.upload_part_request(input);
+        Ok(request.generate_presigned_url(&try!(self.credentials_provider.credentials()), expires_in))
+    }
+
+    fn upload_part_request<'b>(&self, input: &UploadPartRequest) -> SignedRequest<'b> {
         let mut path = String::from("/");
-        if !is_dns_compatible(&input.bucket) {
+        if !self.use_virtual_hosting(&input.bucket) {
             path = format!("{}{}/", path, &input.bucket);
         }
         path = format!("{}{}", path, &input.key);
-        let mut request = SignedRequest::new("PUT", "s3", self.region, &path);
-
-        request.set_payload(input.body);
+        let mut request = SignedRequest::new("PUT", "s3", self.region.clone(), &path);
+        request.set_endpoint(self.endpoint.clone());
 
-        if is_dns_compatible(&input.bucket) {
+        if self.use_virtual_hosting(&input.bucket) {
             let hostname = self.hostname(Some(&input.bucket));
             request.set_hostname(Some(hostname));
         }
@@ -9357,32 +9536,78 @@ impl<P, D> S3Client<P, D> where P: ProvideAwsCredentials, D: DispatchSignedReque
         params.put("partNumber", &format!("{}", part_number));
         params.put("uploadId", upload_id);
         request.set_params(params);
+        request
+    }
 
-        let mut result = try!(sign_and_execute(&self.dispatcher, &mut request, try!(self.credentials_provider.credentials())));
+    fn upload_part_result(result: HttpResponse) -> Result<(String, ResponseMetadata), S3Error> {
+        let metadata = ResponseMetadata::from_response(&result);
         let status = result.status;
 
         match status {
             200 => {
                 match result.headers.get("ETag") {
-                    Some(ref value) => Ok(value.to_string()),
-                    None => Err(S3Error::new("Couldn't find etag in response headers."))
+                    Some(ref value) => Ok((value.to_string(), metadata)),
+                    None => Err(S3Error::new("Couldn't find etag in response headers.").with_metadata(metadata))
                 }
             }
             _ => {
                 println!("Error: Status code was {}", status);
-                println!("Error response body: {}", result.body);
-                Err(S3Error::new("error: didn't get a 200."))
+                println!("Error response body: {}", String::from_utf8_lossy(&result.body));
+                Err(S3Error::new("error: didn't get a 200.").with_response(&result, metadata))
             }
         }
     }
     /// Adds an object to a bucket.
     pub fn put_object(&self, input: &PutObjectRequest) -> Result<PutObjectOutput, S3Error> {
+        self.put_object_with_metadata(input).map(|(output, _)| output)
+    }
+
+    /// Like `put_object`, but also returns the metadata of the response.
+    pub fn put_object_with_metadata(&self, input: &PutObjectRequest) -> Result<(PutObjectOutput, ResponseMetadata), S3Error> {
+        try!(input.payload_signing.check_digest().map_err(S3Error::new));
+        let mut request = try!(self.put_object_request(input));
+        request.set_payload(input.body);
+        request.set_payload_signing(input.payload_signing.clone());
+
+        let result = try!(sign_and_execute(&self.dispatcher, &mut request, try!(self.credentials_provider.credentials()), &self.retry_policy, &self.interceptors));
+        S3Client::<P,D>::put_object_result(result)
+    }
+
+    /// Adds an object to a bucket, reading its contents from `body` as they are sent.
+    ///
+    /// `body` must yield exactly `length` bytes.  `input.body` is ignored.  Since the contents
+    /// can only be read once, the request is not retried.
+    pub fn put_object_streaming<R>(&self, input: &PutObjectRequest, body: R, length: u64) -> Result<PutObjectOutput, S3Error> where R: Read + Send {
+        self.put_object_streaming_with_metadata(input, body, length).map(|(output, _)| output)
+    }
+
+    /// Like `put_object_streaming`, but also returns the metadata of the response.
+    pub fn put_object_streaming_with_metadata<R>(&self, input: &PutObjectRequest, body: R, length: u64) -> Result<(PutObjectOutput, ResponseMetadata), S3Error> where R: Read + Send {
+        let mut request = try!(self.put_object_request(input));
+        request.set_payload_stream(body, length);
+
+        let result = try!(sign_and_execute(&self.dispatcher, &mut request, try!(self.credentials_provider.credentials()), &self.retry_policy, &self.interceptors));
+        S3Client::<P,D>::put_object_result(result)
+    }
+
+    /// A URL anyone can use to upload an object until `expires_in` has passed, without
+    /// credentials of their own.  `input.body` is ignored.
+    ///
+    /// Headers for the other fields of `input` that are set, such as `x-amz-acl` or
+    /// `x-amz-meta-*`, are signed, so the upload has to send them with the same values.
+    pub fn presigned_put_object_url(&self, input: &PutObjectRequest, expires_in: Duration) -> Result<String, S3Error> {
+        let mut request = try!(self.put_object_request(input));
+        Ok(request.generate_presigned_url(&try!(self.credentials_provider.credentials()), expires_in))
+    }
+
+    fn put_object_request<'b>(&self, input: &PutObjectRequest) -> Result<SignedRequest<'b>, S3Error> {
         let mut uri = String::from("/");
-        if !is_dns_compatible(&input.bucket) {
+        if !self.use_virtual_hosting(&input.bucket) {
             uri = format!("{}{}/", uri, &input.bucket);
         }
         uri = format!("{}{}", uri, &input.key);
-        let mut request = SignedRequest::new("PUT", "s3", self.region, &uri);
+        let mut request = SignedRequest::new("PUT", "s3", self.region.clone(), &uri);
+        request.set_endpoint(self.endpoint.clone());
 
         if let Some(ref class) = input.storage_class {
             request.add_header("x-amz-storage-class", class);
@@ -9425,249 +9650,329 @@ impl<P, D> S3Client<P, D> where P: ProvideAwsCredentials, D: DispatchSignedReque
             None => request.set_content_type("binary/octet-stream".to_string())
         };
 
-        if is_dns_compatible(&input.bucket) {
+        if self.use_virtual_hosting(&input.bucket) {
             let hostname = self.hostname(Some(&input.bucket));
             request.set_hostname(Some(hostname));
         }
-        request.set_payload(input.body);
 
-        let mut result = try!(sign_and_execute(&self.dispatcher, &mut request, try!(self.credentials_provider.credentials())));
+        Ok(request)
+    }
+
+    fn put_object_result(result: HttpResponse) -> Result<(PutObjectOutput, ResponseMetadata), S3Error> {
+        let metadata = ResponseMetadata::from_response(&result);
         let status = result.status;
 
         match status {
             200 => {
                 let mut put_result = PutObjectOutput::default();
 
-                Ok(put_result)
+                Ok((put_result, metadata))
             }
             _ => {
                 println!("Error: Status code was {}", status);
-                println!("Error response body: {}", result.body);
-                Err(S3Error::new("error uploading object to S3"))
+                println!("Error response body: {}", String::from_utf8_lossy(&result.body));
+                Err(S3Error::new("error uploading object to S3").with_response(&result, metadata))
             }
         }
     }
     /// Deletes the cors configuration information set for the bucket.
     pub fn delete_bucket_cors(&self, input: &DeleteBucketCorsRequest) -> Result<(), S3Error> {
-        let mut request = SignedRequest::new("DELETE", "s3", self.region, "/{Bucket}?cors");
+        self.delete_bucket_cors_with_metadata(input).map(|(output, _)| output)
+    }
+
+    /// Like `delete_bucket_cors`, but also returns the metadata of the response.
+    pub fn delete_bucket_cors_with_metadata(&self, input: &DeleteBucketCorsRequest) -> Result<((), ResponseMetadata), S3Error> {
+        let mut request = SignedRequest::new("DELETE", "s3", self.region.clone(), "/{Bucket}?cors");
+        request.set_endpoint(self.endpoint.clone());
         let mut params = Params::new();
         params.put("Action", "DeleteBucketCors");
         DeleteBucketCorsRequestWriter::write_params(&mut params, "", input);
         request.set_params(params);
-        let result = try!(sign_and_execute(&self.dispatcher, &mut request, try!(self.credentials_provider.credentials())));
+        let result = try!(sign_and_execute(&self.dispatcher, &mut request, try!(self.credentials_provider.credentials()), &self.retry_policy, &self.interceptors));
+        let metadata = ResponseMetadata::from_response(&result);
         let status = result.status;
-        let mut reader = EventReader::from_str(&result.body);
+        let mut reader = EventReader::new(&result.body[..]);
         let mut stack = XmlResponse::new(reader.events().peekable());
         stack.next(); // xml start tag
         stack.next();
         match status {
             200 => {
-                Ok(())
+                Ok(((), metadata))
             }
-            _ => { Err(S3Error::new(format!("Unexpected HTTP status code {}", status))) }
+            _ => { Err(S3Error::from_response(&result, metadata)) }
         }
     }
     /// Sets the versioning state of an existing bucket. To set the versioning state,
     /// you must be the bucket owner.
     pub fn put_bucket_versioning(&self, input: &PutBucketVersioningRequest) -> Result<(), S3Error> {
-        let mut request = SignedRequest::new("PUT", "s3", self.region, "/{Bucket}?versioning");
+        self.put_bucket_versioning_with_metadata(input).map(|(output, _)| output)
+    }
+
+    /// Like `put_bucket_versioning`, but also returns the metadata of the response.
+    pub fn put_bucket_versioning_with_metadata(&self, input: &PutBucketVersioningRequest) -> Result<((), ResponseMetadata), S3Error> {
+        let mut request = SignedRequest::new("PUT", "s3", self.region.clone(), "/{Bucket}?versioning");
+        request.set_endpoint(self.endpoint.clone());
         let mut params = Params::new();
         params.put("Action", "PutBucketVersioning");
         PutBucketVersioningRequestWriter::write_params(&mut params, "", input);
         request.set_params(params);
-        let result = try!(sign_and_execute(&self.dispatcher, &mut request, try!(self.credentials_provider.credentials())));
+        let result = try!(sign_and_execute(&self.dispatcher, &mut request, try!(self.credentials_provider.credentials()), &self.retry_policy, &self.interceptors));
+        let metadata = ResponseMetadata::from_response(&result);
         let status = result.status;
-        let mut reader = EventReader::from_str(&result.body);
+        let mut reader = EventReader::new(&result.body[..]);
         let mut stack = XmlResponse::new(reader.events().peekable());
         stack.next(); // xml start tag
         stack.next();
         match status {
             200 => {
-                Ok(())
+                Ok(((), metadata))
             }
-            _ => { Err(S3Error::new(format!("Unexpected HTTP status code {}", status))) }
+            _ => { Err(S3Error::from_response(&result, metadata)) }
         }
     }
     /// Returns the cors configuration for the bucket.
     pub fn get_bucket_cors(&self, input: &GetBucketCorsRequest) -> Result<GetBucketCorsOutput, S3Error> {
-        let mut request = SignedRequest::new("GET", "s3", self.region, "/{Bucket}?cors");
+        self.get_bucket_cors_with_metadata(input).map(|(output, _)| output)
+    }
+
+    /// Like `get_bucket_cors`, but also returns the metadata of the response.
+    pub fn get_bucket_cors_with_metadata(&self, input: &GetBucketCorsRequest) -> Result<(GetBucketCorsOutput, ResponseMetadata), S3Error> {
+        let mut request = SignedRequest::new("GET", "s3", self.region.clone(), "/{Bucket}?cors");
+        request.set_endpoint(self.endpoint.clone());
         let mut params = Params::new();
         params.put("Action", "GetBucketCors");
         GetBucketCorsRequestWriter::write_params(&mut params, "", input);
         request.set_params(params);
-        let result = try!(sign_and_execute(&self.dispatcher, &mut request, try!(self.credentials_provider.credentials())));
+        let result = try!(sign_and_execute(&self.dispatcher, &mut request, try!(self.credentials_provider.credentials()), &self.retry_policy, &self.interceptors));
+        let metadata = ResponseMetadata::from_response(&result);
         let status = result.status;
-        let mut reader = EventReader::from_str(&result.body);
+        let mut reader = EventReader::new(&result.body[..]);
         let mut stack = XmlResponse::new(reader.events().peekable());
         stack.next(); // xml start tag
         stack.next();
         match status {
             200 => {
-                Ok(try!(GetBucketCorsOutputParser::parse_xml("GetBucketCorsOutput", &mut stack)))
+                Ok((try!(GetBucketCorsOutputParser::parse_xml("GetBucketCorsOutput", &mut stack)), metadata))
             }
-            _ => { Err(S3Error::new(format!("Unexpected HTTP status code {}", status))) }
+            _ => { Err(S3Error::from_response(&result, metadata)) }
         }
     }
     /// Sets lifecycle configuration for your bucket. If a lifecycle configuration
     /// exists, it replaces it.
     pub fn put_bucket_lifecycle(&self, input: &PutBucketLifecycleRequest) -> Result<(), S3Error> {
-        let mut request = SignedRequest::new("PUT", "s3", self.region, "/{Bucket}?lifecycle");
+        self.put_bucket_lifecycle_with_metadata(input).map(|(output, _)| output)
+    }
+
+    /// Like `put_bucket_lifecycle`, but also returns the metadata of the response.
+    pub fn put_bucket_lifecycle_with_metadata(&self, input: &PutBucketLifecycleRequest) -> Result<((), ResponseMetadata), S3Error> {
+        let mut request = SignedRequest::new("PUT", "s3", self.region.clone(), "/{Bucket}?lifecycle");
+        request.set_endpoint(self.endpoint.clone());
         let mut params = Params::new();
         params.put("Action", "PutBucketLifecycle");
         PutBucketLifecycleRequestWriter::write_params(&mut params, "", input);
         request.set_params(params);
-        let result = try!(sign_and_execute(&self.dispatcher, &mut request, try!(self.credentials_provider.credentials())));
+        let result = try!(sign_and_execute(&self.dispatcher, &mut request, try!(self.credentials_provider.credentials()), &self.retry_policy, &self.interceptors));
+        let metadata = ResponseMetadata::from_response(&result);
         let status = result.status;
-        let mut reader = EventReader::from_str(&result.body);
+        let mut reader = EventReader::new(&result.body[..]);
         let mut stack = XmlResponse::new(reader.events().peekable());
         stack.next(); // xml start tag
         stack.next();
         match status {
             200 => {
-                Ok(())
+                Ok(((), metadata))
             }
-            _ => { Err(S3Error::new(format!("Unexpected HTTP status code {}", status))) }
+            _ => { Err(S3Error::from_response(&result, metadata)) }
         }
     }
     /// Gets the access control policy for the bucket.
     pub fn get_bucket_acl(&self, input: &GetBucketAclRequest) -> Result<GetBucketAclOutput, S3Error> {
-        let mut request = SignedRequest::new("GET", "s3", self.region, "/{Bucket}?acl");
+        self.get_bucket_acl_with_metadata(input).map(|(output, _)| output)
+    }
+
+    /// Like `get_bucket_acl`, but also returns the metadata of the response.
+    pub fn get_bucket_acl_with_metadata(&self, input: &GetBucketAclRequest) -> Result<(GetBucketAclOutput, ResponseMetadata), S3Error> {
+        let mut request = SignedRequest::new("GET", "s3", self.region.clone(), "/{Bucket}?acl");
+        request.set_endpoint(self.endpoint.clone());
         let mut params = Params::new();
         params.put("Action", "GetBucketAcl");
         GetBucketAclRequestWriter::write_params(&mut params, "", input);
         request.set_params(params);
-        let result = try!(sign_and_execute(&self.dispatcher, &mut request, try!(self.credentials_provider.credentials())));
+        let result = try!(sign_and_execute(&self.dispatcher, &mut request, try!(self.credentials_provider.credentials()), &self.retry_policy, &self.interceptors));
+        let metadata = ResponseMetadata::from_response(&result);
         let status = result.status;
-        let mut reader = EventReader::from_str(&result.body);
+        let mut reader = EventReader::new(&result.body[..]);
         let mut stack = XmlResponse::new(reader.events().peekable());
         stack.next(); // xml start tag
         stack.next();
         match status {
             200 => {
-                Ok(try!(GetBucketAclOutputParser::parse_xml("GetBucketAclOutput", &mut stack)))
+                Ok((try!(GetBucketAclOutputParser::parse_xml("GetBucketAclOutput", &mut stack)), metadata))
             }
-            _ => { Err(S3Error::new(format!("Unexpected HTTP status code {}", status))) }
+            _ => { Err(S3Error::from_response(&result, metadata)) }
         }
     }
     /// Returns the logging status of a bucket and the permissions users have to view
     /// and modify that status. To use GET, you must be the bucket owner.
     pub fn get_bucket_logging(&self, input: &GetBucketLoggingRequest) -> Result<GetBucketLoggingOutput, S3Error> {
-        let mut request = SignedRequest::new("GET", "s3", self.region, "/{Bucket}?logging");
+        self.get_bucket_logging_with_metadata(input).map(|(output, _)| output)
+    }
+
+    /// Like `get_bucket_logging`, but also returns the metadata of the response.
+    pub fn get_bucket_logging_with_metadata(&self, input: &GetBucketLoggingRequest) -> Result<(GetBucketLoggingOutput, ResponseMetadata), S3Error> {
+        let mut request = SignedRequest::new("GET", "s3", self.region.clone(), "/{Bucket}?logging");
+        request.set_endpoint(self.endpoint.clone());
         let mut params = Params::new();
         params.put("Action", "GetBucketLogging");
         GetBucketLoggingRequestWriter::write_params(&mut params, "", input);
         request.set_params(params);
-        let result = try!(sign_and_execute(&self.dispatcher, &mut request, try!(self.credentials_provider.credentials())));
+        let result = try!(sign_and_execute(&self.dispatcher, &mut request, try!(self.credentials_provider.credentials()), &self.retry_policy, &self.interceptors));
+        let metadata = ResponseMetadata::from_response(&result);
         let status = result.status;
-        let mut reader = EventReader::from_str(&result.body);
+        let mut reader = EventReader::new(&result.body[..]);
         let mut stack = XmlResponse::new(reader.events().peekable());
         stack.next(); // xml start tag
         stack.next();
         match status {
             200 => {
-                Ok(try!(GetBucketLoggingOutputParser::parse_xml("GetBucketLoggingOutput", &mut stack)))
+                Ok((try!(GetBucketLoggingOutputParser::parse_xml("GetBucketLoggingOutput", &mut stack)), metadata))
             }
-            _ => { Err(S3Error::new(format!("Unexpected HTTP status code {}", status))) }
+            _ => { Err(S3Error::from_response(&result, metadata)) }
         }
     }
     /// This operation is useful to determine if a bucket exists and you have
     /// permission to access it.
     pub fn head_bucket(&self, input: &HeadBucketRequest) -> Result<(), S3Error> {
-        let mut request = SignedRequest::new("HEAD", "s3", self.region, "/{Bucket}");
+        self.head_bucket_with_metadata(input).map(|(output, _)| output)
+    }
+
+    /// Like `head_bucket`, but also returns the metadata of the response.
+    pub fn head_bucket_with_metadata(&self, input: &HeadBucketRequest) -> Result<((), ResponseMetadata), S3Error> {
+        let mut request = SignedRequest::new("HEAD", "s3", self.region.clone(), "/{Bucket}");
+        request.set_endpoint(self.endpoint.clone());
         let mut params = Params::new();
         params.put("Action", "HeadBucket");
         HeadBucketRequestWriter::write_params(&mut params, "", input);
         request.set_params(params);
-        let result = try!(sign_and_execute(&self.dispatcher, &mut request, try!(self.credentials_provider.credentials())));
+        let result = try!(sign_and_execute(&self.dispatcher, &mut request, try!(self.credentials_provider.credentials()), &self.retry_policy, &self.interceptors));
+        let metadata = ResponseMetadata::from_response(&result);
         let status = result.status;
-        let mut reader = EventReader::from_str(&result.body);
+        let mut reader = EventReader::new(&result.body[..]);
         let mut stack = XmlResponse::new(reader.events().peekable());
         stack.next(); // xml start tag
         stack.next();
         match status {
             200 => {
-                Ok(())
+                Ok(((), metadata))
             }
-            _ => { Err(S3Error::new(format!("Unexpected HTTP status code {}", status))) }
+            _ => { Err(S3Error::from_response(&result, metadata)) }
         }
     }
     /// Sets the permissions on a bucket using access control lists (ACL).
     pub fn put_bucket_acl(&self, input: &PutBucketAclRequest) -> Result<(), S3Error> {
-        let mut request = SignedRequest::new("PUT", "s3", self.region, "/{Bucket}?acl");
+        self.put_bucket_acl_with_metadata(input).map(|(output, _)| output)
+    }
+
+    /// Like `put_bucket_acl`, but also returns the metadata of the response.
+    pub fn put_bucket_acl_with_metadata(&self, input: &PutBucketAclRequest) -> Result<((), ResponseMetadata), S3Error> {
+        let mut request = SignedRequest::new("PUT", "s3", self.region.clone(), "/{Bucket}?acl");
+        request.set_endpoint(self.endpoint.clone());
         let mut params = Params::new();
         params.put("Action", "PutBucketAcl");
         request.set_params(params);
-        let result = try!(sign_and_execute(&self.dispatcher, &mut request, try!(self.credentials_provider.credentials())));
+        let result = try!(sign_and_execute(&self.dispatcher, &mut request, try!(self.credentials_provider.credentials()), &self.retry_policy, &self.interceptors));
+        let metadata = ResponseMetadata::from_response(&result);
         let status = result.status;
-        let mut reader = EventReader::from_str(&result.body);
+        let mut reader = EventReader::new(&result.body[..]);
         let mut stack = XmlResponse::new(reader.events().peekable());
         stack.next(); // xml start tag
         stack.next();
         match status {
             200 => {
-                Ok(())
+                Ok(((), metadata))
             }
-            _ => { Err(S3Error::new(format!("Unexpected HTTP status code {}", status))) }
+            _ => { Err(S3Error::from_response(&result, metadata)) }
         }
     }
     /// This operation removes the website configuration from the bucket.
     pub fn delete_bucket_website(&self, input: &DeleteBucketWebsiteRequest) -> Result<(), S3Error> {
-        let mut request = SignedRequest::new("DELETE", "s3", self.region, "/{Bucket}?website");
+        self.delete_bucket_website_with_metadata(input).map(|(output, _)| output)
+    }
+
+    /// Like `delete_bucket_website`, but also returns the metadata of the response.
+    pub fn delete_bucket_website_with_metadata(&self, input: &DeleteBucketWebsiteRequest) -> Result<((), ResponseMetadata), S3Error> {
+        let mut request = SignedRequest::new("DELETE", "s3", self.region.clone(), "/{Bucket}?website");
+        request.set_endpoint(self.endpoint.clone());
         let mut params = Params::new();
         params.put("Action", "DeleteBucketWebsite");
         DeleteBucketWebsiteRequestWriter::write_params(&mut params, "", input);
         request.set_params(params);
-        let result = try!(sign_and_execute(&self.dispatcher, &mut request, try!(self.credentials_provider.credentials())));
+        let result = try!(sign_and_execute(&self.dispatcher, &mut request, try!(self.credentials_provider.credentials()), &self.retry_policy, &self.interceptors));
+        let metadata = ResponseMetadata::from_response(&result);
         let status = result.status;
-        let mut reader = EventReader::from_str(&result.body);
+        let mut reader = EventReader::new(&result.body[..]);
         let mut stack = XmlResponse::new(reader.events().peekable());
         stack.next(); // xml start tag
         stack.next();
         match status {
             200 => {
-                Ok(())
+                Ok(((), metadata))
             }
-            _ => { Err(S3Error::new(format!("Unexpected HTTP status code {}", status))) }
+            _ => { Err(S3Error::from_response(&result, metadata)) }
         }
     }
     /// Deletes the policy from the bucket.
     pub fn delete_bucket_policy(&self, input: &DeleteBucketPolicyRequest) -> Result<(), S3Error> {
-        let mut request = SignedRequest::new("DELETE", "s3", self.region, "/{Bucket}?policy");
+        self.delete_bucket_policy_with_metadata(input).map(|(output, _)| output)
+    }
+
+    /// Like `delete_bucket_policy`, but also returns the metadata of the response.
+    pub fn delete_bucket_policy_with_metadata(&self, input: &DeleteBucketPolicyRequest) -> Result<((), ResponseMetadata), S3Error> {
+        let mut request = SignedRequest::new("DELETE", "s3", self.region.clone(), "/{Bucket}?policy");
+        request.set_endpoint(self.endpoint.clone());
         let mut params = Params::new();
         params.put("Action", "DeleteBucketPolicy");
         DeleteBucketPolicyRequestWriter::write_params(&mut params, "", input);
         request.set_params(params);
-        let result = try!(sign_and_execute(&self.dispatcher, &mut request, try!(self.credentials_provider.credentials())));
+        let result = try!(sign_and_execute(&self.dispatcher, &mut request, try!(self.credentials_provider.credentials()), &self.retry_policy, &self.interceptors));
+        let metadata = ResponseMetadata::from_response(&result);
         let status = result.status;
-        let mut reader = EventReader::from_str(&result.body);
+        let mut reader = EventReader::new(&result.body[..]);
         let mut stack = XmlResponse::new(reader.events().peekable());
         stack.next(); // xml start tag
         stack.next();
         match status {
             200 => {
-                Ok(())
+                Ok(((), metadata))
             }
-            _ => { Err(S3Error::new(format!("Unexpected HTTP status code {}", status))) }
+            _ => { Err(S3Error::from_response(&result, metadata)) }
         }
     }
     /// Returns the notification configuration of a bucket.
     pub fn get_bucket_notification_configuration(&self, input: &GetBucketNotificationConfigurationRequest) -> Result<NotificationConfiguration, S3Error> {
-        let mut request = SignedRequest::new("GET", "s3", self.region, "/{Bucket}?notification");
+        self.get_bucket_notification_configuration_with_metadata(input).map(|(output, _)| output)
+    }
+
+    /// Like `get_bucket_notification_configuration`, but also returns the metadata of the response.
+    pub fn get_bucket_notification_configuration_with_metadata(&self, input: &GetBucketNotificationConfigurationRequest) -> Result<(NotificationConfiguration, ResponseMetadata), S3Error> {
+        let mut request = SignedRequest::new("GET", "s3", self.region.clone(), "/{Bucket}?notification");
+        request.set_endpoint(self.endpoint.clone());
         let mut params = Params::new();
         params.put("Action", "GetBucketNotificationConfiguration");
         GetBucketNotificationConfigurationRequestWriter::write_params(&mut params, "", input);
         request.set_params(params);
-        let result = try!(sign_and_execute(&self.dispatcher, &mut request, try!(self.credentials_provider.credentials())));
+        let result = try!(sign_and_execute(&self.dispatcher, &mut request, try!(self.credentials_provider.credentials()), &self.retry_policy, &self.interceptors));
+        let metadata = ResponseMetadata::from_response(&result);
         let status = result.status;
-        let mut reader = EventReader::from_str(&result.body);
+        let mut reader = EventReader::new(&result.body[..]);
         let mut stack = XmlResponse::new(reader.events().peekable());
         stack.next(); // xml start tag
         stack.next();
         match status {
             200 => {
-                Ok(try!(NotificationConfigurationParser::parse_xml("NotificationConfiguration", &mut stack)))
+                Ok((try!(NotificationConfigurationParser::parse_xml("NotificationConfiguration", &mut stack)), metadata))
             }
-            _ => { Err(S3Error::new(format!("Unexpected HTTP status code {}", status))) }
+            _ => { Err(S3Error::from_response(&result, metadata)) }
         }
     }
     /// This operation enables you to delete multiple objects from a bucket using a
@@ -9675,7 +9980,8 @@ impl<P, D> S3Client<P, D> where P: ProvideAwsCredentials, D: DispatchSignedReque
     pub fn delete_objects(&self, input: &DeleteObjectsRequest) -> Result<DeleteObjectsOutput, S3Error> {
         // let mut uri = String::from("/");
         // uri = uri +  &input.key.to_string();
-        // let mut request = SignedRequest::new("DELETE", "s3", self.region, &uri);
+        // let mut request = SignedRequest::new("DELETE", "s3", self.region.clone(), &uri);
+        // request.set_endpoint(self.endpoint.clone());
         // let mut params = Params::new();
         //
         // let hostname = self.hostname(Some(&input.bucket));
@@ -9684,7 +9990,7 @@ impl<P, D> S3Client<P, D> where P: ProvideAwsCredentials, D: DispatchSignedReque
         // params.put("Action", "DeleteObjects");
         // DeleteObjectsRequestWriter::write_params(&mut params, "", input);
         // request.set_params(params);
-        // let result = try!(sign_and_execute(&self.dispatcher, &mut request, try!(self.credentials_provider.credentials())));
+        // let result = try!(sign_and_execute(&self.dispatcher, &mut request, try!(self.credentials_provider.credentials()), &self.retry_policy, &self.interceptors));
         // let status = result.status;
         // match status {
         //  200 => {
@@ -9695,54 +10001,75 @@ impl<P, D> S3Client<P, D> where P: ProvideAwsCredentials, D: DispatchSignedReque
         Err(S3Error::new("not implemented"))
     }
     pub fn delete_bucket_replication(&self, input: &DeleteBucketReplicationRequest) -> Result<(), S3Error> {
-        let mut request = SignedRequest::new("DELETE", "s3", self.region, "/{Bucket}?replication");
+        self.delete_bucket_replication_with_metadata(input).map(|(output, _)| output)
+    }
+
+    /// Like `delete_bucket_replication`, but also returns the metadata of the response.
+    pub fn delete_bucket_replication_with_metadata(&self, input: &DeleteBucketReplicationRequest) -> Result<((), ResponseMetadata), S3Error> {
+        let mut request = SignedRequest::new("DELETE", "s3", self.region.clone(), "/{Bucket}?replication");
+        request.set_endpoint(self.endpoint.clone());
         let mut params = Params::new();
         params.put("Action", "DeleteBucketReplication");
         DeleteBucketReplicationRequestWriter::write_params(&mut params, "", input);
         request.set_params(params);
-        let result = try!(sign_and_execute(&self.dispatcher, &mut request, try!(self.credentials_provider.credentials())));
+        let result = try!(sign_and_execute(&self.dispatcher, &mut request, try!(self.credentials_provider.credentials()), &self.retry_policy, &self.interceptors));
+        let metadata = ResponseMetadata::from_response(&result);
         let status = result.status;
-        let mut reader = EventReader::from_str(&result.body);
+        let mut reader = EventReader::new(&result.body[..]);
         let mut stack = XmlResponse::new(reader.events().peekable());
         stack.next(); // xml start tag
         stack.next();
         match status {
             200 => {
-                Ok(())
+                Ok(((), metadata))
             }
-            _ => { Err(S3Error::new(format!("Unexpected HTTP status code {}", status))) }
+            _ => { Err(S3Error::from_response(&result, metadata)) }
         }
     }
     /// Creates a copy of an object that is already stored in Amazon S3.
     pub fn copy_object(&self, input: &CopyObjectRequest) -> Result<CopyObjectOutput, S3Error> {
-        let mut request = SignedRequest::new("PUT", "s3", self.region, "/{Bucket}/{Key+}");
+        self.copy_object_with_metadata(input).map(|(output, _)| output)
+    }
+
+    /// Like `copy_object`, but also returns the metadata of the response.
+    pub fn copy_object_with_metadata(&self, input: &CopyObjectRequest) -> Result<(CopyObjectOutput, ResponseMetadata), S3Error> {
+        let mut request = SignedRequest::new("PUT", "s3", self.region.clone(), "/{Bucket}/{Key+}");
+        request.set_endpoint(self.endpoint.clone());
         let mut params = Params::new();
         params.put("Action", "CopyObject");
         CopyObjectRequestWriter::write_params(&mut params, "", input);
         request.set_params(params);
-        let result = try!(sign_and_execute(&self.dispatcher, &mut request, try!(self.credentials_provider.credentials())));
+        let result = try!(sign_and_execute(&self.dispatcher, &mut request, try!(self.credentials_provider.credentials()), &self.retry_policy, &self.interceptors));
+        let metadata = ResponseMetadata::from_response(&result);
         let status = result.status;
-        let mut reader = EventReader::from_str(&result.body);
+        let mut reader = EventReader::new(&result.body[..]);
         let mut stack = XmlResponse::new(reader.events().peekable());
         stack.next(); // xml start tag
         stack.next();
         match status {
             200 => {
-                Ok(try!(CopyObjectOutputParser::parse_xml("CopyObjectOutput", &mut stack)))
+                Ok((try!(CopyObjectOutputParser::parse_xml("CopyObjectOutput", &mut stack)), metadata))
             }
-            _ => { Err(S3Error::new(format!("Unexpected HTTP status code {}", status))) }
+            _ => { Err(S3Error::from_response(&result, metadata)) }
         }
     }
     /// Returns a list of all buckets owned by the authenticated sender of the
     /// request.
     pub fn list_buckets(&self) -> Result<ListBucketsOutput, S3Error> {
-        let mut request = SignedRequest::new("GET", "s3", self.region, "/");
+        self.list_buckets_with_metadata().map(|(output, _)| output)
+    }
+
+    /// Like `list_buckets`, but also returns the metadata of the response.
+    pub fn list_buckets_with_metadata(&self) -> Result<(ListBucketsOutput, ResponseMetadata), S3Error> {
+        let mut request = SignedRequest::new("GET", "s3", self.region.clone(), "/");
+        request.set_endpoint(self.endpoint.clone());
         let mut params = Params::new();
         params.put("Action", "ListBuckets");
         request.set_params(params);
-        let result = try!(sign_and_execute(&self.dispatcher, &mut request, try!(self.credentials_provider.credentials())));
+        let result = try!(sign_and_execute(&self.dispatcher, &mut request, try!(self.credentials_provider.credentials()), &self.retry_policy, &self.interceptors));
+        let metadata = ResponseMetadata::from_response(&result);
         let status = result.status;
-        let mut reader = EventReader::from_str(&result.body);
+        let mut reader = EventReader::new(&result.body[..]);
         let mut stack = XmlResponse::new(reader.events().peekable());
 
         stack.next(); // xml start tag
@@ -9750,9 +10077,9 @@ impl<P, D> S3Client<P, D> where P: ProvideAwsCredentials, D: DispatchSignedReque
         match status {
             200 => {
                 // was "ListBucketsOutput"
-                Ok(try!(ListBucketsOutputParser::parse_xml("ListAllMyBucketsResult", &mut stack)))
+                Ok((try!(ListBucketsOutputParser::parse_xml("ListAllMyBucketsResult", &mut stack)), metadata))
             }
-            _ => { Err(S3Error::new(format!("Unexpected HTTP status code {}", status))) }
+            _ => { Err(S3Error::from_response(&result, metadata)) }
         }
     }
     /// Sets the request payment configuration for a bucket. By default, the bucket
@@ -9762,57 +10089,77 @@ impl<P, D> S3Client<P, D> where P: ProvideAwsCredentials, D: DispatchSignedReque
     /// be found at
     /// http://docs.aws.amazon.com/AmazonS3/latest/dev/RequesterPaysBuckets.html
     pub fn put_bucket_request_payment(&self, input: &PutBucketRequestPaymentRequest) -> Result<(), S3Error> {
-        let mut request = SignedRequest::new("PUT", "s3", self.region, "/{Bucket}?requestPayment");
+        self.put_bucket_request_payment_with_metadata(input).map(|(output, _)| output)
+    }
+
+    /// Like `put_bucket_request_payment`, but also returns the metadata of the response.
+    pub fn put_bucket_request_payment_with_metadata(&self, input: &PutBucketRequestPaymentRequest) -> Result<((), ResponseMetadata), S3Error> {
+        let mut request = SignedRequest::new("PUT", "s3", self.region.clone(), "/{Bucket}?requestPayment");
+        request.set_endpoint(self.endpoint.clone());
         let mut params = Params::new();
         params.put("Action", "PutBucketRequestPayment");
         PutBucketRequestPaymentRequestWriter::write_params(&mut params, "", input);
         request.set_params(params);
-        let result = try!(sign_and_execute(&self.dispatcher, &mut request, try!(self.credentials_provider.credentials())));
+        let result = try!(sign_and_execute(&self.dispatcher, &mut request, try!(self.credentials_provider.credentials()), &self.retry_policy, &self.interceptors));
+        let metadata = ResponseMetadata::from_response(&result);
         let status = result.status;
-        let mut reader = EventReader::from_str(&result.body);
+        let mut reader = EventReader::new(&result.body[..]);
         let mut stack = XmlResponse::new(reader.events().peekable());
         stack.next(); // xml start tag
         stack.next();
         match status {
             200 => {
-                Ok(())
+                Ok(((), metadata))
             }
-            _ => { Err(S3Error::new(format!("Unexpected HTTP status code {}", status))) }
+            _ => { Err(S3Error::from_response(&result, metadata)) }
         }
     }
     /// Enables notifications of specified events for a bucket.
     pub fn put_bucket_notification_configuration(&self, input: &PutBucketNotificationConfigurationRequest) -> Result<(), S3Error> {
-        let mut request = SignedRequest::new("PUT", "s3", self.region, "/{Bucket}?notification");
+        self.put_bucket_notification_configuration_with_metadata(input).map(|(output, _)| output)
+    }
+
+    /// Like `put_bucket_notification_configuration`, but also returns the metadata of the response.
+    pub fn put_bucket_notification_configuration_with_metadata(&self, input: &PutBucketNotificationConfigurationRequest) -> Result<((), ResponseMetadata), S3Error> {
+        let mut request = SignedRequest::new("PUT", "s3", self.region.clone(), "/{Bucket}?notification");
+        request.set_endpoint(self.endpoint.clone());
         let mut params = Params::new();
         params.put("Action", "PutBucketNotificationConfiguration");
         PutBucketNotificationConfigurationRequestWriter::write_params(&mut params, "", input);
         request.set_params(params);
-        let result = try!(sign_and_execute(&self.dispatcher, &mut request, try!(self.credentials_provider.credentials())));
+        let result = try!(sign_and_execute(&self.dispatcher, &mut request, try!(self.credentials_provider.credentials()), &self.retry_policy, &self.interceptors));
+        let metadata = ResponseMetadata::from_response(&result);
         let status = result.status;
-        let mut reader = EventReader::from_str(&result.body);
+        let mut reader = EventReader::new(&result.body[..]);
         let mut stack = XmlResponse::new(reader.events().peekable());
         stack.next(); // xml start tag
         stack.next();
         match status {
             200 => {
-                Ok(())
+                Ok(((), metadata))
             }
-            _ => { Err(S3Error::new(format!("Unexpected HTTP status code {}", status))) }
+            _ => { Err(S3Error::from_response(&result, metadata)) }
         }
     }
     /// The HEAD operation retrieves metadata from an object without returning the
     /// object itself. This operation is useful if you're only interested in an
     /// object's metadata. To use HEAD, you must have READ access to the object.
     pub fn head_object(&self, input: &HeadObjectRequest) -> Result<HeadObjectOutput, S3Error> {
+        self.head_object_with_metadata(input).map(|(output, _)| output)
+    }
+
+    /// Like `head_object`, but also returns the metadata of the response.
+    pub fn head_object_with_metadata(&self, input: &HeadObjectRequest) -> Result<(HeadObjectOutput, ResponseMetadata), S3Error> {
         let mut path = String::from("/");
-        if !is_dns_compatible(&input.bucket) {
+        if !self.use_virtual_hosting(&input.bucket) {
             path = format!("{}{}/", path, &input.bucket);
         }
         path = format!("{}{}", path, &input.key);
-        let mut request = SignedRequest::new("HEAD", "s3", self.region, &path);
+        let mut request = SignedRequest::new("HEAD", "s3", self.region.clone(), &path);
+        request.set_endpoint(self.endpoint.clone());
         let mut params = Params::new();
 
-        if is_dns_compatible(&input.bucket) {
+        if self.use_virtual_hosting(&input.bucket) {
             let hostname = self.hostname(Some(&input.bucket));
             request.set_hostname(Some(hostname));
         }
@@ -9821,94 +10168,122 @@ impl<P, D> S3Client<P, D> where P: ProvideAwsCredentials, D: DispatchSignedReque
 
         HeadObjectRequestWriter::write_params(&mut params, "", input);
         request.set_params(params);
-        let mut result = try!(sign_and_execute(&self.dispatcher, &mut request, try!(self.credentials_provider.credentials())));
+        let mut result = try!(sign_and_execute(&self.dispatcher, &mut request, try!(self.credentials_provider.credentials()), &self.retry_policy, &self.interceptors));
+        let metadata = ResponseMetadata::from_response(&result);
         let status = result.status;
         match status {
             200 => {
                 let head_object = try!(S3Client::<P,D>::head_object_from_response(&mut result));
 
-                Ok(head_object)
+                Ok((head_object, metadata))
             }
-            _ => { Err(S3Error::new(format!("Unexpected HTTP status code {}", status))) }
+            _ => { Err(S3Error::from_response(&result, metadata)) }
         }
     }
     /// Deletes the tags from the bucket.
     pub fn delete_bucket_tagging(&self, input: &DeleteBucketTaggingRequest) -> Result<(), S3Error> {
-        let mut request = SignedRequest::new("DELETE", "s3", self.region, "/{Bucket}?tagging");
+        self.delete_bucket_tagging_with_metadata(input).map(|(output, _)| output)
+    }
+
+    /// Like `delete_bucket_tagging`, but also returns the metadata of the response.
+    pub fn delete_bucket_tagging_with_metadata(&self, input: &DeleteBucketTaggingRequest) -> Result<((), ResponseMetadata), S3Error> {
+        let mut request = SignedRequest::new("DELETE", "s3", self.region.clone(), "/{Bucket}?tagging");
+        request.set_endpoint(self.endpoint.clone());
         let mut params = Params::new();
         params.put("Action", "DeleteBucketTagging");
         DeleteBucketTaggingRequestWriter::write_params(&mut params, "", input);
         request.set_params(params);
-        let result = try!(sign_and_execute(&self.dispatcher, &mut request, try!(self.credentials_provider.credentials())));
+        let result = try!(sign_and_execute(&self.dispatcher, &mut request, try!(self.credentials_provider.credentials()), &self.retry_policy, &self.interceptors));
+        let metadata = ResponseMetadata::from_response(&result);
         let status = result.status;
-        let mut reader = EventReader::from_str(&result.body);
+        let mut reader = EventReader::new(&result.body[..]);
         let mut stack = XmlResponse::new(reader.events().peekable());
         stack.next(); // xml start tag
         stack.next();
         match status {
             200 => {
-                Ok(())
+                Ok(((), metadata))
             }
-            _ => { Err(S3Error::new(format!("Unexpected HTTP status code {}", status))) }
+            _ => { Err(S3Error::from_response(&result, metadata)) }
         }
     }
     /// Return torrent files from a bucket.
     pub fn get_object_torrent(&self, input: &GetObjectTorrentRequest) -> Result<GetObjectTorrentOutput, S3Error> {
-        let mut request = SignedRequest::new("GET", "s3", self.region, "/{Bucket}/{Key+}?torrent");
+        self.get_object_torrent_with_metadata(input).map(|(output, _)| output)
+    }
+
+    /// Like `get_object_torrent`, but also returns the metadata of the response.
+    pub fn get_object_torrent_with_metadata(&self, input: &GetObjectTorrentRequest) -> Result<(GetObjectTorrentOutput, ResponseMetadata), S3Error> {
+        let mut request = SignedRequest::new("GET", "s3", self.region.clone(), "/{Bucket}/{Key+}?torrent");
+        request.set_endpoint(self.endpoint.clone());
         let mut params = Params::new();
         params.put("Action", "GetObjectTorrent");
         GetObjectTorrentRequestWriter::write_params(&mut params, "", input);
         request.set_params(params);
-        let result = try!(sign_and_execute(&self.dispatcher, &mut request, try!(self.credentials_provider.credentials())));
+        let result = try!(sign_and_execute(&self.dispatcher, &mut request, try!(self.credentials_provider.credentials()), &self.retry_policy, &self.interceptors));
+        let metadata = ResponseMetadata::from_response(&result);
         let status = result.status;
-        let mut reader = EventReader::from_str(&result.body);
+        let mut reader = EventReader::new(&result.body[..]);
         let mut stack = XmlResponse::new(reader.events().peekable());
         stack.next(); // xml start tag
         stack.next();
         match status {
             200 => {
-                Ok(try!(GetObjectTorrentOutputParser::parse_xml("GetObjectTorrentOutput", &mut stack)))
+                Ok((try!(GetObjectTorrentOutputParser::parse_xml("GetObjectTorrentOutput", &mut stack)), metadata))
             }
-            _ => { Err(S3Error::new(format!("Unexpected HTTP status code {}", status))) }
+            _ => { Err(S3Error::from_response(&result, metadata)) }
         }
     }
     /// Returns the lifecycle configuration information set on the bucket.
     pub fn get_bucket_lifecycle(&self, input: &GetBucketLifecycleRequest) -> Result<GetBucketLifecycleOutput, S3Error> {
-        let mut request = SignedRequest::new("GET", "s3", self.region, "/{Bucket}?lifecycle");
+        self.get_bucket_lifecycle_with_metadata(input).map(|(output, _)| output)
+    }
+
+    /// Like `get_bucket_lifecycle`, but also returns the metadata of the response.
+    pub fn get_bucket_lifecycle_with_metadata(&self, input: &GetBucketLifecycleRequest) -> Result<(GetBucketLifecycleOutput, ResponseMetadata), S3Error> {
+        let mut request = SignedRequest::new("GET", "s3", self.region.clone(), "/{Bucket}?lifecycle");
+        request.set_endpoint(self.endpoint.clone());
         let mut params = Params::new();
         params.put("Action", "GetBucketLifecycle");
         GetBucketLifecycleRequestWriter::write_params(&mut params, "", input);
         request.set_params(params);
-        let result = try!(sign_and_execute(&self.dispatcher, &mut request, try!(self.credentials_provider.credentials())));
+        let result = try!(sign_and_execute(&self.dispatcher, &mut request, try!(self.credentials_provider.credentials()), &self.retry_policy, &self.interceptors));
+        let metadata = ResponseMetadata::from_response(&result);
         let status = result.status;
-        let mut reader = EventReader::from_str(&result.body);
+        let mut reader = EventReader::new(&result.body[..]);
         let mut stack = XmlResponse::new(reader.events().peekable());
         stack.next(); // xml start tag
         stack.next();
         match status {
             200 => {
-                Ok(try!(GetBucketLifecycleOutputParser::parse_xml("GetBucketLifecycleOutput", &mut stack)))
+                Ok((try!(GetBucketLifecycleOutputParser::parse_xml("GetBucketLifecycleOutput", &mut stack)), metadata))
             }
-            _ => { Err(S3Error::new(format!("Unexpected HTTP status code {}", status))) }
+            _ => { Err(S3Error::from_response(&result, metadata)) }
         }
     }
     /// Creates a new bucket.
     /// All requests go to the us-east-1/us-standard endpoint, but can create buckets anywhere.
     pub fn create_bucket(&self, input: &CreateBucketRequest) -> Result<CreateBucketOutput, S3Error> {
+        self.create_bucket_with_metadata(input).map(|(output, _)| output)
+    }
+
+    /// Like `create_bucket`, but also returns the metadata of the response.
+    pub fn create_bucket_with_metadata(&self, input: &CreateBucketRequest) -> Result<(CreateBucketOutput, ResponseMetadata), S3Error> {
         let region = Region::UsEast1;
         let mut create_config : Vec<u8>;
         let mut path = String::from("/");
-        if !is_dns_compatible(&input.bucket) {
+        if !self.use_virtual_hosting(&input.bucket) {
             path = format!("{}{}/", path, &input.bucket);
         }
         let mut request = SignedRequest::new("PUT", "s3", region, &path);
-        if is_dns_compatible(&input.bucket) {
+        request.set_endpoint(self.endpoint.clone().or_else(|| self.region.endpoint()));
+        if self.use_virtual_hosting(&input.bucket) {
             let hostname = self.hostname(Some(&input.bucket));
             request.set_hostname(Some(hostname));
         }
 
-        if needs_create_bucket_config(self.region) {
-            create_config = create_bucket_config_xml(self.region);
+        if needs_create_bucket_config(self.region.clone()) {
+            create_config = create_bucket_config_xml(self.region.clone());
             request.set_payload(Some(&create_config));
         }
 
@@ -9917,134 +10292,164 @@ impl<P, D> S3Client<P, D> where P: ProvideAwsCredentials, D: DispatchSignedReque
             Some(ref canned_acl) => request.add_header("x-amz-acl", &canned_acl_in_aws_format(canned_acl)),
         }
 
-        let result = try!(sign_and_execute(&self.dispatcher, &mut request, try!(self.credentials_provider.credentials())));
+        let result = try!(sign_and_execute(&self.dispatcher, &mut request, try!(self.credentials_provider.credentials()), &self.retry_policy, &self.interceptors));
+        let metadata = ResponseMetadata::from_response(&result);
         let status = result.status;
 
         match status {
             200 => {
                 match result.headers.get("Location") {
-                    Some(ref value) => Ok(CreateBucketOutput{ location: value.to_string() }),
-                    None => Err(S3Error::new("Something went wrong when creating a bucket."))
+                    Some(ref value) => Ok((CreateBucketOutput{ location: value.to_string() }, metadata)),
+                    None => Err(S3Error::new("Something went wrong when creating a bucket.").with_metadata(metadata))
                 }
             }
-            _ => { Err(S3Error::new(format!("Unexpected HTTP status code {}", status))) }
+            _ => { Err(S3Error::from_response(&result, metadata)) }
         }
     }
     /// Completes a multipart upload by assembling previously uploaded parts.
     pub fn complete_multipart_upload(&self, input: &CompleteMultipartUploadRequest) -> Result<CompleteMultipartUploadOutput, S3Error> {
+        self.complete_multipart_upload_with_metadata(input).map(|(output, _)| output)
+    }
+
+    /// Like `complete_multipart_upload`, but also returns the metadata of the response.
+    pub fn complete_multipart_upload_with_metadata(&self, input: &CompleteMultipartUploadRequest) -> Result<(CompleteMultipartUploadOutput, ResponseMetadata), S3Error> {
         let mut path = String::from("/");
-        if !is_dns_compatible(&input.bucket) {
+        if !self.use_virtual_hosting(&input.bucket) {
             path = format!("{}{}/", path, &input.bucket);
         }
         path = format!("{}{}", path, &input.key);
-        let mut request = SignedRequest::new("POST", "s3", self.region,
+        let mut request = SignedRequest::new("POST", "s3", self.region.clone(),
             &path);
+        request.set_endpoint(self.endpoint.clone());
 
         let mut params = Params::new();
         params.put("uploadId", &input.upload_id.to_string());
         request.set_params(params);
 
-        if is_dns_compatible(&input.bucket) {
+        if self.use_virtual_hosting(&input.bucket) {
             let hostname = self.hostname(Some(&input.bucket));
             request.set_hostname(Some(hostname));
         }
 
         request.set_payload(input.multipart_upload);
 
-        let mut result = try!(sign_and_execute(&self.dispatcher, &mut request, try!(self.credentials_provider.credentials())));
+        let mut result = try!(sign_and_execute(&self.dispatcher, &mut request, try!(self.credentials_provider.credentials()), &self.retry_policy, &self.interceptors));
+        let metadata = ResponseMetadata::from_response(&result);
         let status = result.status;
 
         match status {
             200 => {
-                let mut reader = EventReader::from_str(&result.body);
+                let mut reader = EventReader::new(&result.body[..]);
                 let mut stack = XmlResponse::new(reader.events().peekable());
                 stack.next(); // xml start tag
 
-                Ok(try!(CompleteMultipartUploadOutputParser::parse_xml("CompleteMultipartUploadResult", &mut stack)))
+                Ok((try!(CompleteMultipartUploadOutputParser::parse_xml("CompleteMultipartUploadResult", &mut stack)), metadata))
             }
             _ => {
-                println!("Error response body: {}", result.body);
-                Err(S3Error::new(format!("Unexpected HTTP status code {}", status)))
+                println!("Error response body: {}", String::from_utf8_lossy(&result.body));
+                Err(S3Error::from_response(&result, metadata))
             }
         }
     }
     /// Returns the website configuration for a bucket.
     pub fn get_bucket_website(&self, input: &GetBucketWebsiteRequest) -> Result<GetBucketWebsiteOutput, S3Error> {
-        let mut request = SignedRequest::new("GET", "s3", self.region, "/{Bucket}?website");
+        self.get_bucket_website_with_metadata(input).map(|(output, _)| output)
+    }
+
+    /// Like `get_bucket_website`, but also returns the metadata of the response.
+    pub fn get_bucket_website_with_metadata(&self, input: &GetBucketWebsiteRequest) -> Result<(GetBucketWebsiteOutput, ResponseMetadata), S3Error> {
+        let mut request = SignedRequest::new("GET", "s3", self.region.clone(), "/{Bucket}?website");
+        request.set_endpoint(self.endpoint.clone());
         let mut params = Params::new();
         params.put("Action", "GetBucketWebsite");
         GetBucketWebsiteRequestWriter::write_params(&mut params, "", input);
         request.set_params(params);
-        let result = try!(sign_and_execute(&self.dispatcher, &mut request, try!(self.credentials_provider.credentials())));
+        let result = try!(sign_and_execute(&self.dispatcher, &mut request, try!(self.credentials_provider.credentials()), &self.retry_policy, &self.interceptors));
+        let metadata = ResponseMetadata::from_response(&result);
         let status = result.status;
-        let mut reader = EventReader::from_str(&result.body);
+        let mut reader = EventReader::new(&result.body[..]);
         let mut stack = XmlResponse::new(reader.events().peekable());
         stack.next(); // xml start tag
         stack.next();
         match status {
             200 => {
-                Ok(try!(GetBucketWebsiteOutputParser::parse_xml("GetBucketWebsiteOutput", &mut stack)))
+                Ok((try!(GetBucketWebsiteOutputParser::parse_xml("GetBucketWebsiteOutput", &mut stack)), metadata))
             }
-            _ => { Err(S3Error::new(format!("Unexpected HTTP status code {}", status))) }
+            _ => { Err(S3Error::from_response(&result, metadata)) }
         }
     }
     /// Initiates a multipart upload and returns an upload ID.
     /// **Note:** After you initiate multipart upload and upload one or more parts, you must either complete or abort multipart upload in order to stop getting charged for storage of the uploaded parts. Only after you either complete or abort multipart upload, Amazon S3 frees up the parts storage and stops charging you for the parts storage.
     pub fn create_multipart_upload(&self, input: &CreateMultipartUploadRequest) -> Result<CreateMultipartUploadOutput, S3Error> {
+        self.create_multipart_upload_with_metadata(input).map(|(output, _)| output)
+    }
+
+    /// Like `create_multipart_upload`, but also returns the metadata of the response.
+    pub fn create_multipart_upload_with_metadata(&self, input: &CreateMultipartUploadRequest) -> Result<(CreateMultipartUploadOutput, ResponseMetadata), S3Error> {
 
         let mut path = String::from("/");
-        if !is_dns_compatible(&input.bucket) {
+        if !self.use_virtual_hosting(&input.bucket) {
             path = format!("{}{}/", path, &input.bucket);
         }
         path = format!("{}{}", path, &input.key);
-        let mut request = SignedRequest::new("POST", "s3", self.region, &path);
+        let mut request = SignedRequest::new("POST", "s3", self.region.clone(), &path);
+        request.set_endpoint(self.endpoint.clone());
 
         let mut params = Params::new();
         params.put("uploads", "");
         request.set_params(params);
 
-        if is_dns_compatible(&input.bucket) {
+        if self.use_virtual_hosting(&input.bucket) {
             let hostname = self.hostname(Some(&input.bucket));
             request.set_hostname(Some(hostname));
         }
 
-        let result = try!(sign_and_execute(&self.dispatcher, &mut request, try!(self.credentials_provider.credentials())));
+        let result = try!(sign_and_execute(&self.dispatcher, &mut request, try!(self.credentials_provider.credentials()), &self.retry_policy, &self.interceptors));
+
+        let metadata = ResponseMetadata::from_response(&result);
         let status = result.status;
 
-        let mut reader = EventReader::from_str(&result.body);
+        let mut reader = EventReader::new(&result.body[..]);
         let mut stack = XmlResponse::new(reader.events().peekable());
         stack.next(); // xml start tag
         match status {
             200 => {
-                Ok(try!(CreateMultipartUploadOutputParser::parse_xml("InitiateMultipartUploadResult", &mut stack)))
+                Ok((try!(CreateMultipartUploadOutputParser::parse_xml("InitiateMultipartUploadResult", &mut stack)), metadata))
             }
-            _ => { Err(S3Error::new(format!("Unexpected HTTP status code {}", status))) }
+            _ => { Err(S3Error::from_response(&result, metadata)) }
         }
     }
     /// Deletes the bucket. All objects (including all object versions and Delete
     /// Markers) in the bucket must be deleted before the bucket itself can be
     /// deleted.
     pub fn delete_bucket(&self, input: &DeleteBucketRequest, region: Region) -> Result<(), S3Error> {
+        self.delete_bucket_with_metadata(input, region).map(|(output, _)| output)
+    }
+
+    /// Like `delete_bucket`, but also returns the metadata of the response.
+    pub fn delete_bucket_with_metadata(&self, input: &DeleteBucketRequest, region: Region) -> Result<((), ResponseMetadata), S3Error> {
         let mut path = String::from("/");
-        if !is_dns_compatible(&input.bucket) {
+        if !self.use_virtual_hosting(&input.bucket) {
             path = format!("{}{}/", path, &input.bucket);
         }
         let mut request = SignedRequest::new("DELETE", "s3", region, &path);
+        request.set_endpoint(self.endpoint.clone());
 
-        if is_dns_compatible(&input.bucket) {
+        if self.use_virtual_hosting(&input.bucket) {
             let hostname = self.hostname(Some(&input.bucket));
             request.set_hostname(Some(hostname));
         }
 
-        let mut result = try!(sign_and_execute(&self.dispatcher, &mut request, try!(self.credentials_provider.credentials())));
+        let mut result = try!(sign_and_execute(&self.dispatcher, &mut request, try!(self.credentials_provider.credentials()), &self.retry_policy, &self.interceptors));
+        let metadata = ResponseMetadata::from_response(&result);
         let status = result.status;
         match status {
             204 => {
-                Ok(())
+                Ok(((), metadata))
             }
             _ => {
-                println!("Error response body: {}", result.body);
-                Err(S3Error::new(format!("delete bucket error, status was {}", status)))
+                println!("Error response body: {}", String::from_utf8_lossy(&result.body));
+                Err(S3Error::new(format!("delete bucket error, status was {}", status)).with_response(&result, metadata))
             }
         }
     }
@@ -10080,7 +10485,7 @@ impl<P, D> S3Client<P, D> where P: ProvideAwsCredentials, D: DispatchSignedReque
         let cache_control = try!(S3Client::<P,D>::get_value_for_header("Cache-Control".to_string(), response));
 
         let content_length_string = try!(S3Client::<P,D>::get_value_for_header("Content-Length".to_string(), response));
-        let content_length = try!(content_length_string.parse::<i32>());
+        let content_length = try!(content_length_string.parse::<ContentLength>());
 
         let expiration = try!(S3Client::<P,D>::get_value_for_header("x-amz-expiration".to_string(), response));
         let missing_meta_string = try!(S3Client::<P,D>::get_value_for_header("x-amz-missing-meta".to_string(), response));
@@ -10153,7 +10558,7 @@ impl<P, D> S3Client<P, D> where P: ProvideAwsCredentials, D: DispatchSignedReque
         let expires = try!(S3Client::<P,D>::get_value_for_header("Expires".to_string(), response));
         let cache_control = try!(S3Client::<P,D>::get_value_for_header("Cache-Control".to_string(), response));
         let content_length_string = try!(S3Client::<P,D>::get_value_for_header("Content-Length".to_string(), response));
-        let content_length = try!(content_length_string.parse::<i32>());
+        let content_length = try!(content_length_string.parse::<ContentLength>());
         let expiration = try!(S3Client::<P,D>::get_value_for_header("x-amz-expiration".to_string(), response));
         let missing_meta_string = try!(S3Client::<P,D>::get_value_for_header("x-amz-missing-meta".to_string(), response));
         let missing_meta : i32;
@@ -10183,7 +10588,7 @@ impl<P, D> S3Client<P, D> where P: ProvideAwsCredentials, D: DispatchSignedReque
             ssekms_key_id: ssekms_key_id,
             content_disposition: content_disposition,
             metadata: HashMap::new(),
-            body: response.body.clone().into_bytes(),
+            body: mem::replace(&mut response.body, Vec::new()),
             website_redirect_location: website_redirect_location,
             expires: expires,
             cache_control: cache_control,
@@ -10203,168 +10608,281 @@ impl<P, D> S3Client<P, D> where P: ProvideAwsCredentials, D: DispatchSignedReque
 
     /// Retrieves objects from Amazon S3.
     pub fn get_object(&self, input: &GetObjectRequest) -> Result<GetObjectOutput, S3Error> {
+        self.get_object_with_metadata(input).map(|(output, _)| output)
+    }
+
+    /// Like `get_object`, but also returns the metadata of the response.
+    pub fn get_object_with_metadata(&self, input: &GetObjectRequest) -> Result<(GetObjectOutput, ResponseMetadata), S3Error> {
+        let mut request = self.get_object_request(input);
+        let mut result = try!(sign_and_execute(&self.dispatcher, &mut request, try!(self.credentials_provider.credentials()), &self.retry_policy, &self.interceptors));
+        let metadata = ResponseMetadata::from_response(&result);
+        let status = result.status;
+
+        match status {
+            200 | 206 => {
+                let s3_object = try!(S3Client::<P,D>::get_object_from_response(&mut result));
+
+                Ok((s3_object, metadata))
+            }
+            _ => {
+                debug!("Error: Status code was {}", status);
+                debug!("Error response body: {}", String::from_utf8_lossy(&result.body));
+                Err(S3Error::new("error in get_object").with_response(&result, metadata))
+            }
+        }
+    }
+
+    /// Retrieves an object from Amazon S3 without buffering its contents in memory.
+    ///
+    /// The returned output implements `Read`; the object is read from the connection as the
+    /// caller consumes it, which makes this suitable for objects too large to hold in memory.
+    pub fn get_object_streaming(&self, input: &GetObjectRequest) -> Result<GetObjectStreamingOutput, S3Error> {
+        self.get_object_streaming_with_metadata(input).map(|(output, _)| output)
+    }
+
+    /// Like `get_object_streaming`, but also returns the metadata of the response.
+    pub fn get_object_streaming_with_metadata(&self, input: &GetObjectRequest) -> Result<(GetObjectStreamingOutput, ResponseMetadata), S3Error> {
+        let mut request = self.get_object_request(input);
+        let result = try!(sign_and_execute_streaming(&self.dispatcher, &mut request, try!(self.credentials_provider.credentials()), &self.retry_policy, &self.interceptors));
+
+        match result.status {
+            // 206 is the answer to a ranged GET
+            200 | 206 => {
+                let metadata = ResponseMetadata::from_streaming_response(&result);
+
+                // parse the headers on their own, the body is handed to the caller untouched
+                let mut headers_only = HttpResponse {
+                    status: result.status,
+                    body: Vec::new(),
+                    headers: result.headers,
+                };
+                let output = try!(S3Client::<P,D>::get_object_from_response(&mut headers_only));
+
+                Ok((GetObjectStreamingOutput {
+                    output: output,
+                    body: result.body,
+                }, metadata))
+            }
+            status => {
+                let result = try!(result.buffer());
+                debug!("Error: Status code was {}", status);
+                debug!("Error response body: {}", String::from_utf8_lossy(&result.body));
+                Err(S3Error::new("error in get_object_streaming").with_response(&result, ResponseMetadata::from_response(&result)))
+            }
+        }
+    }
+
+    /// A URL anyone can use to download the object until `expires_in` has passed, without
+    /// credentials of their own.
+    pub fn presigned_get_object_url(&self, input: &GetObjectRequest, expires_in: Duration) -> Result<String, S3Error> {
+        let mut request = self.get_object_request(input);
+        Ok(request.generate_presigned_url(&try!(self.credentials_provider.credentials()), expires_in))
+    }
+
+    /// A policy for browser uploads to `bucket`, posted to the same endpoint as this client's
+    /// requests, which S3 accepts until `expires_in` after it is signed.
+    pub fn post_policy(&self, bucket: &str, expires_in: Duration) -> PostPolicy {
+        PostPolicy::new(bucket, self.region.clone(), expires_in).with_endpoint(self.endpoint.clone())
+    }
+
+    fn get_object_request(&self, input: &GetObjectRequest) -> SignedRequest {
         let mut path = String::from("/");
-        if !is_dns_compatible(&input.bucket) {
+        if !self.use_virtual_hosting(&input.bucket) {
             path = format!("{}{}/", path, &input.bucket);
         }
         path = format!("{}{}", path, &input.key);
-        let mut request = SignedRequest::new("GET", "s3", self.region, &path);
+        let mut request = SignedRequest::new("GET", "s3", self.region.clone(), &path);
+        request.set_endpoint(self.endpoint.clone());
         let mut params = Params::new();
 
-        if is_dns_compatible(&input.bucket) {
+        if self.use_virtual_hosting(&input.bucket) {
             let hostname = self.hostname(Some(&input.bucket));
             request.set_hostname(Some(hostname));
         }
 
+        // S3 only honours a range sent as a header
+        if let Some(ref range) = input.range {
+            request.add_header("Range", range);
+        }
+
         params.put("Action", "GetObject");
         GetObjectRequestWriter::write_params(&mut params, "", input);
 
         request.set_params(params);
-        let mut result = try!(sign_and_execute(&self.dispatcher, &mut request, try!(self.credentials_provider.credentials())));
-        let status = result.status;
-
-        match status {
-            200 => {
-                let s3_object = try!(S3Client::<P,D>::get_object_from_response(&mut result));
-
-                Ok(s3_object)
-            }
-            _ => {
-                println!("Error: Status code was {}", status);
-                println!("Error response body: {}", result.body);
-                Err(S3Error::new("error in get_object"))
-            }
-        }
+        request
     }
 
     /// Returns the policy of a specified bucket.
     pub fn get_bucket_policy(&self, input: &GetBucketPolicyRequest) -> Result<GetBucketPolicyOutput, S3Error> {
-        let mut request = SignedRequest::new("GET", "s3", self.region, "/{Bucket}?policy");
+        self.get_bucket_policy_with_metadata(input).map(|(output, _)| output)
+    }
+
+    /// Like `get_bucket_policy`, but also returns the metadata of the response.
+    pub fn get_bucket_policy_with_metadata(&self, input: &GetBucketPolicyRequest) -> Result<(GetBucketPolicyOutput, ResponseMetadata), S3Error> {
+        let mut request = SignedRequest::new("GET", "s3", self.region.clone(), "/{Bucket}?policy");
+        request.set_endpoint(self.endpoint.clone());
         let mut params = Params::new();
         params.put("Action", "GetBucketPolicy");
         GetBucketPolicyRequestWriter::write_params(&mut params, "", input);
         request.set_params(params);
-        let result = try!(sign_and_execute(&self.dispatcher, &mut request, try!(self.credentials_provider.credentials())));
+        let result = try!(sign_and_execute(&self.dispatcher, &mut request, try!(self.credentials_provider.credentials()), &self.retry_policy, &self.interceptors));
+        let metadata = ResponseMetadata::from_response(&result);
         let status = result.status;
-        let mut reader = EventReader::from_str(&result.body);
+        let mut reader = EventReader::new(&result.body[..]);
         let mut stack = XmlResponse::new(reader.events().peekable());
         stack.next(); // xml start tag
         stack.next();
         match status {
             200 => {
-                Ok(try!(GetBucketPolicyOutputParser::parse_xml("GetBucketPolicyOutput", &mut stack)))
+                Ok((try!(GetBucketPolicyOutputParser::parse_xml("GetBucketPolicyOutput", &mut stack)), metadata))
             }
-            _ => { Err(S3Error::new(format!("Unexpected HTTP status code {}", status))) }
+            _ => { Err(S3Error::from_response(&result, metadata)) }
         }
     }
     /// Returns the versioning state of a bucket.
     pub fn get_bucket_versioning(&self, input: &GetBucketVersioningRequest) -> Result<GetBucketVersioningOutput, S3Error> {
-        let mut request = SignedRequest::new("GET", "s3", self.region, "/{Bucket}?versioning");
+        self.get_bucket_versioning_with_metadata(input).map(|(output, _)| output)
+    }
+
+    /// Like `get_bucket_versioning`, but also returns the metadata of the response.
+    pub fn get_bucket_versioning_with_metadata(&self, input: &GetBucketVersioningRequest) -> Result<(GetBucketVersioningOutput, ResponseMetadata), S3Error> {
+        let mut request = SignedRequest::new("GET", "s3", self.region.clone(), "/{Bucket}?versioning");
+        request.set_endpoint(self.endpoint.clone());
         let mut params = Params::new();
         params.put("Action", "GetBucketVersioning");
         GetBucketVersioningRequestWriter::write_params(&mut params, "", input);
         request.set_params(params);
-        let result = try!(sign_and_execute(&self.dispatcher, &mut request, try!(self.credentials_provider.credentials())));
+        let result = try!(sign_and_execute(&self.dispatcher, &mut request, try!(self.credentials_provider.credentials()), &self.retry_policy, &self.interceptors));
+        let metadata = ResponseMetadata::from_response(&result);
         let status = result.status;
-        let mut reader = EventReader::from_str(&result.body);
+        let mut reader = EventReader::new(&result.body[..]);
         let mut stack = XmlResponse::new(reader.events().peekable());
         stack.next(); // xml start tag
         stack.next();
         match status {
             200 => {
-                Ok(try!(GetBucketVersioningOutputParser::parse_xml("GetBucketVersioningOutput", &mut stack)))
+                Ok((try!(GetBucketVersioningOutputParser::parse_xml("GetBucketVersioningOutput", &mut stack)), metadata))
             }
-            _ => { Err(S3Error::new(format!("Unexpected HTTP status code {}", status))) }
+            _ => { Err(S3Error::from_response(&result, metadata)) }
         }
     }
     /// This operation lists in-progress multipart uploads.
     pub fn list_multipart_uploads(&self, input: &ListMultipartUploadsRequest) -> Result<ListMultipartUploadsOutput, S3Error> {
+        self.list_multipart_uploads_with_metadata(input).map(|(output, _)| output)
+    }
+
+    /// Like `list_multipart_uploads`, but also returns the metadata of the response.
+    pub fn list_multipart_uploads_with_metadata(&self, input: &ListMultipartUploadsRequest) -> Result<(ListMultipartUploadsOutput, ResponseMetadata), S3Error> {
         let mut path = String::from("/");
-        if !is_dns_compatible(&input.bucket) {
+        if !self.use_virtual_hosting(&input.bucket) {
             path = format!("{}{}/", path, &input.bucket);
         }
-        let mut request = SignedRequest::new("GET", "s3", self.region, &path);
+        let mut request = SignedRequest::new("GET", "s3", self.region.clone(), &path);
+        request.set_endpoint(self.endpoint.clone());
 
         let mut params = Params::new();
         params.put("uploads", "");
         request.set_params(params);
 
-        if is_dns_compatible(&input.bucket) {
+        if self.use_virtual_hosting(&input.bucket) {
             let hostname = self.hostname(Some(&input.bucket));
             request.set_hostname(Some(hostname));
         }
 
-        let result = try!(sign_and_execute(&self.dispatcher, &mut request, try!(self.credentials_provider.credentials())));
+        let result = try!(sign_and_execute(&self.dispatcher, &mut request, try!(self.credentials_provider.credentials()), &self.retry_policy, &self.interceptors));
+
+        let metadata = ResponseMetadata::from_response(&result);
         let status = result.status;
-        let mut reader = EventReader::from_str(&result.body);
+        let mut reader = EventReader::new(&result.body[..]);
         let mut stack = XmlResponse::new(reader.events().peekable());
         stack.next(); // xml start tag
 
         match status {
             200 => {
-                Ok(try!(ListMultipartUploadsOutputParser::parse_xml("ListMultipartUploadsResult", &mut stack)))
+                Ok((try!(ListMultipartUploadsOutputParser::parse_xml("ListMultipartUploadsResult", &mut stack)), metadata))
             }
-            _ => { Err(S3Error::new(format!("Unexpected HTTP status code {}", status))) }
+            _ => { Err(S3Error::from_response(&result, metadata)) }
         }
     }
     /// Returns the request payment configuration of a bucket.
     pub fn get_bucket_request_payment(&self, input: &GetBucketRequestPaymentRequest) -> Result<GetBucketRequestPaymentOutput, S3Error> {
-        let mut request = SignedRequest::new("GET", "s3", self.region, "/{Bucket}?requestPayment");
+        self.get_bucket_request_payment_with_metadata(input).map(|(output, _)| output)
+    }
+
+    /// Like `get_bucket_request_payment`, but also returns the metadata of the response.
+    pub fn get_bucket_request_payment_with_metadata(&self, input: &GetBucketRequestPaymentRequest) -> Result<(GetBucketRequestPaymentOutput, ResponseMetadata), S3Error> {
+        let mut request = SignedRequest::new("GET", "s3", self.region.clone(), "/{Bucket}?requestPayment");
+        request.set_endpoint(self.endpoint.clone());
         let mut params = Params::new();
         params.put("Action", "GetBucketRequestPayment");
         GetBucketRequestPaymentRequestWriter::write_params(&mut params, "", input);
         request.set_params(params);
-        let result = try!(sign_and_execute(&self.dispatcher, &mut request, try!(self.credentials_provider.credentials())));
+        let result = try!(sign_and_execute(&self.dispatcher, &mut request, try!(self.credentials_provider.credentials()), &self.retry_policy, &self.interceptors));
+        let metadata = ResponseMetadata::from_response(&result);
         let status = result.status;
-        let mut reader = EventReader::from_str(&result.body);
+        let mut reader = EventReader::new(&result.body[..]);
         let mut stack = XmlResponse::new(reader.events().peekable());
         stack.next(); // xml start tag
         stack.next();
         match status {
             200 => {
-                Ok(try!(GetBucketRequestPaymentOutputParser::parse_xml("GetBucketRequestPaymentOutput", &mut stack)))
+                Ok((try!(GetBucketRequestPaymentOutputParser::parse_xml("GetBucketRequestPaymentOutput", &mut stack)), metadata))
             }
-            _ => { Err(S3Error::new(format!("Unexpected HTTP status code {}", status))) }
+            _ => { Err(S3Error::from_response(&result, metadata)) }
         }
     }
     /// Sets the tags for a bucket.
     pub fn put_bucket_tagging(&self, input: &PutBucketTaggingRequest) -> Result<(), S3Error> {
-        let mut request = SignedRequest::new("PUT", "s3", self.region, "/{Bucket}?tagging");
+        self.put_bucket_tagging_with_metadata(input).map(|(output, _)| output)
+    }
+
+    /// Like `put_bucket_tagging`, but also returns the metadata of the response.
+    pub fn put_bucket_tagging_with_metadata(&self, input: &PutBucketTaggingRequest) -> Result<((), ResponseMetadata), S3Error> {
+        let mut request = SignedRequest::new("PUT", "s3", self.region.clone(), "/{Bucket}?tagging");
+        request.set_endpoint(self.endpoint.clone());
         let mut params = Params::new();
         params.put("Action", "PutBucketTagging");
         PutBucketTaggingRequestWriter::write_params(&mut params, "", input);
         request.set_params(params);
-        let result = try!(sign_and_execute(&self.dispatcher, &mut request, try!(self.credentials_provider.credentials())));
+        let result = try!(sign_and_execute(&self.dispatcher, &mut request, try!(self.credentials_provider.credentials()), &self.retry_policy, &self.interceptors));
+        let metadata = ResponseMetadata::from_response(&result);
         let status = result.status;
-        let mut reader = EventReader::from_str(&result.body);
+        let mut reader = EventReader::new(&result.body[..]);
         let mut stack = XmlResponse::new(reader.events().peekable());
         stack.next(); // xml start tag
         stack.next();
         match status {
             200 => {
-                Ok(())
+                Ok(((), metadata))
             }
-            _ => { Err(S3Error::new(format!("Unexpected HTTP status code {}", status))) }
+            _ => { Err(S3Error::from_response(&result, metadata)) }
         }
     }
     /// Returns the tag set associated with the bucket.
     pub fn get_bucket_tagging(&self, input: &GetBucketTaggingRequest) -> Result<GetBucketTaggingOutput, S3Error> {
-        let mut request = SignedRequest::new("GET", "s3", self.region, "/{Bucket}?tagging");
+        self.get_bucket_tagging_with_metadata(input).map(|(output, _)| output)
+    }
+
+    /// Like `get_bucket_tagging`, but also returns the metadata of the response.
+    pub fn get_bucket_tagging_with_metadata(&self, input: &GetBucketTaggingRequest) -> Result<(GetBucketTaggingOutput, ResponseMetadata), S3Error> {
+        let mut request = SignedRequest::new("GET", "s3", self.region.clone(), "/{Bucket}?tagging");
+        request.set_endpoint(self.endpoint.clone());
         let mut params = Params::new();
         params.put("Action", "GetBucketTagging");
         GetBucketTaggingRequestWriter::write_params(&mut params, "", input);
         request.set_params(params);
-        let result = try!(sign_and_execute(&self.dispatcher, &mut request, try!(self.credentials_provider.credentials())));
+        let result = try!(sign_and_execute(&self.dispatcher, &mut request, try!(self.credentials_provider.credentials()), &self.retry_policy, &self.interceptors));
+        let metadata = ResponseMetadata::from_response(&result);
         let status = result.status;
-        let mut reader = EventReader::from_str(&result.body);
+        let mut reader = EventReader::new(&result.body[..]);
         let mut stack = XmlResponse::new(reader.events().peekable());
         stack.next(); // xml start tag
         stack.next();
         match status {
             200 => {
-                Ok(try!(GetBucketTaggingOutputParser::parse_xml("GetBucketTaggingOutput", &mut stack)))
+                Ok((try!(GetBucketTaggingOutputParser::parse_xml("GetBucketTaggingOutput", &mut stack)), metadata))
             }
-            _ => { Err(S3Error::new(format!("Unexpected HTTP status code {}", status))) }
+            _ => { Err(S3Error::from_response(&result, metadata)) }
         }
     }
     /// Aborts a multipart upload.
@@ -10372,189 +10890,245 @@ impl<P, D> S3Client<P, D> where P: ProvideAwsCredentials, D: DispatchSignedReque
     /// part storage, you should call the List Parts operation and ensure the parts
     /// list is empty.
     pub fn abort_multipart_upload(&self, input: &AbortMultipartUploadRequest) -> Result<AbortMultipartUploadOutput, S3Error> {
+        self.abort_multipart_upload_with_metadata(input).map(|(output, _)| output)
+    }
+
+    /// Like `abort_multipart_upload`, but also returns the metadata of the response.
+    pub fn abort_multipart_upload_with_metadata(&self, input: &AbortMultipartUploadRequest) -> Result<(AbortMultipartUploadOutput, ResponseMetadata), S3Error> {
         let mut path = String::from("/");
-        if !is_dns_compatible(&input.bucket) {
+        if !self.use_virtual_hosting(&input.bucket) {
             path = format!("{}{}/", path, &input.bucket);
         }
         path = format!("{}{}", path, &input.key);
-        let mut request = SignedRequest::new("DELETE", "s3", self.region, &path);
+        let mut request = SignedRequest::new("DELETE", "s3", self.region.clone(), &path);
+        request.set_endpoint(self.endpoint.clone());
 
         let mut params = Params::new();
         params.put("uploadId", &input.upload_id.to_string());
         request.set_params(params);
 
-        if is_dns_compatible(&input.bucket) {
+        if self.use_virtual_hosting(&input.bucket) {
             let hostname = self.hostname(Some(&input.bucket));
             request.set_hostname(Some(hostname));
         }
 
-        let result = try!(sign_and_execute(&self.dispatcher, &mut request, try!(self.credentials_provider.credentials())));
+        let result = try!(sign_and_execute(&self.dispatcher, &mut request, try!(self.credentials_provider.credentials()), &self.retry_policy, &self.interceptors));
+        let metadata = ResponseMetadata::from_response(&result);
         let status = result.status;
-        let mut reader = EventReader::from_str(&result.body);
+        let mut reader = EventReader::new(&result.body[..]);
         let mut stack = XmlResponse::new(reader.events().peekable());
         stack.next(); // xml start tag
 
         match status {
             204 => {
-                Ok(AbortMultipartUploadOutput::default())
+                Ok((AbortMultipartUploadOutput::default(), metadata))
             }
-            _ => { Err(S3Error::new(format!("error, got return code {}", status))) }
+            _ => { Err(S3Error::new(format!("error, got return code {}", status)).with_response(&result, metadata)) }
         }
     }
     /// uses the acl subresource to set the access control list (ACL) permissions for
     /// an object that already exists in a bucket
     pub fn put_object_acl(&self, input: &PutObjectAclRequest) -> Result<PutObjectAclOutput, S3Error> {
-        let mut request = SignedRequest::new("PUT", "s3", self.region, "/{Bucket}/{Key+}?acl");
+        self.put_object_acl_with_metadata(input).map(|(output, _)| output)
+    }
+
+    /// Like `put_object_acl`, but also returns the metadata of the response.
+    pub fn put_object_acl_with_metadata(&self, input: &PutObjectAclRequest) -> Result<(PutObjectAclOutput, ResponseMetadata), S3Error> {
+        let mut request = SignedRequest::new("PUT", "s3", self.region.clone(), "/{Bucket}/{Key+}?acl");
+        request.set_endpoint(self.endpoint.clone());
         let mut params = Params::new();
         params.put("Action", "PutObjectAcl");
         PutObjectAclRequestWriter::write_params(&mut params, "", input);
         request.set_params(params);
-        let result = try!(sign_and_execute(&self.dispatcher, &mut request, try!(self.credentials_provider.credentials())));
+        let result = try!(sign_and_execute(&self.dispatcher, &mut request, try!(self.credentials_provider.credentials()), &self.retry_policy, &self.interceptors));
+        let metadata = ResponseMetadata::from_response(&result);
         let status = result.status;
-        let mut reader = EventReader::from_str(&result.body);
+        let mut reader = EventReader::new(&result.body[..]);
         let mut stack = XmlResponse::new(reader.events().peekable());
         stack.next(); // xml start tag
         stack.next();
         match status {
             200 => {
-                Ok(try!(PutObjectAclOutputParser::parse_xml("PutObjectAclOutput", &mut stack)))
+                Ok((try!(PutObjectAclOutputParser::parse_xml("PutObjectAclOutput", &mut stack)), metadata))
             }
-            _ => { Err(S3Error::new(format!("Unexpected HTTP status code {}", status))) }
+            _ => { Err(S3Error::from_response(&result, metadata)) }
         }
     }
     /// Returns the region the bucket resides in.
     pub fn get_bucket_location(&self, input: &GetBucketLocationRequest) -> Result<GetBucketLocationOutput, S3Error> {
-        let mut request = SignedRequest::new("GET", "s3", self.region, "/{Bucket}?location");
+        self.get_bucket_location_with_metadata(input).map(|(output, _)| output)
+    }
+
+    /// Like `get_bucket_location`, but also returns the metadata of the response.
+    pub fn get_bucket_location_with_metadata(&self, input: &GetBucketLocationRequest) -> Result<(GetBucketLocationOutput, ResponseMetadata), S3Error> {
+        let mut request = SignedRequest::new("GET", "s3", self.region.clone(), "/{Bucket}?location");
+        request.set_endpoint(self.endpoint.clone());
         let mut params = Params::new();
         params.put("Action", "GetBucketLocation");
         GetBucketLocationRequestWriter::write_params(&mut params, "", input);
         request.set_params(params);
-        let result = try!(sign_and_execute(&self.dispatcher, &mut request, try!(self.credentials_provider.credentials())));
+        let result = try!(sign_and_execute(&self.dispatcher, &mut request, try!(self.credentials_provider.credentials()), &self.retry_policy, &self.interceptors));
+        let metadata = ResponseMetadata::from_response(&result);
         let status = result.status;
-        let mut reader = EventReader::from_str(&result.body);
+        let mut reader = EventReader::new(&result.body[..]);
         let mut stack = XmlResponse::new(reader.events().peekable());
         stack.next(); // xml start tag
         stack.next();
         match status {
             200 => {
-                Ok(try!(GetBucketLocationOutputParser::parse_xml("GetBucketLocationOutput", &mut stack)))
+                Ok((try!(GetBucketLocationOutputParser::parse_xml("GetBucketLocationOutput", &mut stack)), metadata))
             }
-            _ => { Err(S3Error::new(format!("Unexpected HTTP status code {}", status))) }
+            _ => { Err(S3Error::from_response(&result, metadata)) }
         }
     }
     /// Sets the cors configuration for a bucket.
     pub fn put_bucket_cors(&self, input: &PutBucketCorsRequest) -> Result<(), S3Error> {
-        let mut request = SignedRequest::new("PUT", "s3", self.region, "/{Bucket}?cors");
+        self.put_bucket_cors_with_metadata(input).map(|(output, _)| output)
+    }
+
+    /// Like `put_bucket_cors`, but also returns the metadata of the response.
+    pub fn put_bucket_cors_with_metadata(&self, input: &PutBucketCorsRequest) -> Result<((), ResponseMetadata), S3Error> {
+        let mut request = SignedRequest::new("PUT", "s3", self.region.clone(), "/{Bucket}?cors");
+        request.set_endpoint(self.endpoint.clone());
         let mut params = Params::new();
         params.put("Action", "PutBucketCors");
         PutBucketCorsRequestWriter::write_params(&mut params, "", input);
         request.set_params(params);
-        let result = try!(sign_and_execute(&self.dispatcher, &mut request, try!(self.credentials_provider.credentials())));
+        let result = try!(sign_and_execute(&self.dispatcher, &mut request, try!(self.credentials_provider.credentials()), &self.retry_policy, &self.interceptors));
+        let metadata = ResponseMetadata::from_response(&result);
         let status = result.status;
-        let mut reader = EventReader::from_str(&result.body);
+        let mut reader = EventReader::new(&result.body[..]);
         let mut stack = XmlResponse::new(reader.events().peekable());
         stack.next(); // xml start tag
         stack.next();
         match status {
             200 => {
-                Ok(())
+                Ok(((), metadata))
             }
-            _ => { Err(S3Error::new(format!("Unexpected HTTP status code {}", status))) }
+            _ => { Err(S3Error::from_response(&result, metadata)) }
         }
     }
     /// Deletes the lifecycle configuration from the bucket.
     pub fn delete_bucket_lifecycle(&self, input: &DeleteBucketLifecycleRequest) -> Result<(), S3Error> {
-        let mut request = SignedRequest::new("DELETE", "s3", self.region, "/{Bucket}?lifecycle");
+        self.delete_bucket_lifecycle_with_metadata(input).map(|(output, _)| output)
+    }
+
+    /// Like `delete_bucket_lifecycle`, but also returns the metadata of the response.
+    pub fn delete_bucket_lifecycle_with_metadata(&self, input: &DeleteBucketLifecycleRequest) -> Result<((), ResponseMetadata), S3Error> {
+        let mut request = SignedRequest::new("DELETE", "s3", self.region.clone(), "/{Bucket}?lifecycle");
+        request.set_endpoint(self.endpoint.clone());
         let mut params = Params::new();
         params.put("Action", "DeleteBucketLifecycle");
         DeleteBucketLifecycleRequestWriter::write_params(&mut params, "", input);
         request.set_params(params);
-        let result = try!(sign_and_execute(&self.dispatcher, &mut request, try!(self.credentials_provider.credentials())));
+        let result = try!(sign_and_execute(&self.dispatcher, &mut request, try!(self.credentials_provider.credentials()), &self.retry_policy, &self.interceptors));
+        let metadata = ResponseMetadata::from_response(&result);
         let status = result.status;
-        let mut reader = EventReader::from_str(&result.body);
+        let mut reader = EventReader::new(&result.body[..]);
         let mut stack = XmlResponse::new(reader.events().peekable());
         stack.next(); // xml start tag
         stack.next();
         match status {
             200 => {
-                Ok(())
+                Ok(((), metadata))
             }
-            _ => { Err(S3Error::new(format!("Unexpected HTTP status code {}", status))) }
+            _ => { Err(S3Error::from_response(&result, metadata)) }
         }
     }
     /// Deprecated, see the GetBucketNotificationConfiguration operation.
     pub fn get_bucket_notification(&self, input: &GetBucketNotificationConfigurationRequest) -> Result<NotificationConfigurationDeprecated, S3Error> {
-        let mut request = SignedRequest::new("GET", "s3", self.region, "/{Bucket}?notification");
+        self.get_bucket_notification_with_metadata(input).map(|(output, _)| output)
+    }
+
+    /// Like `get_bucket_notification`, but also returns the metadata of the response.
+    pub fn get_bucket_notification_with_metadata(&self, input: &GetBucketNotificationConfigurationRequest) -> Result<(NotificationConfigurationDeprecated, ResponseMetadata), S3Error> {
+        let mut request = SignedRequest::new("GET", "s3", self.region.clone(), "/{Bucket}?notification");
+        request.set_endpoint(self.endpoint.clone());
         let mut params = Params::new();
         params.put("Action", "GetBucketNotification");
         GetBucketNotificationConfigurationRequestWriter::write_params(&mut params, "", input);
         request.set_params(params);
-        let result = try!(sign_and_execute(&self.dispatcher, &mut request, try!(self.credentials_provider.credentials())));
+        let result = try!(sign_and_execute(&self.dispatcher, &mut request, try!(self.credentials_provider.credentials()), &self.retry_policy, &self.interceptors));
+        let metadata = ResponseMetadata::from_response(&result);
         let status = result.status;
-        let mut reader = EventReader::from_str(&result.body);
+        let mut reader = EventReader::new(&result.body[..]);
         let mut stack = XmlResponse::new(reader.events().peekable());
         stack.next(); // xml start tag
         stack.next();
         match status {
             200 => {
-                Ok(try!(NotificationConfigurationDeprecatedParser::parse_xml("NotificationConfigurationDeprecated", &mut stack)))
+                Ok((try!(NotificationConfigurationDeprecatedParser::parse_xml("NotificationConfigurationDeprecated", &mut stack)), metadata))
             }
-            _ => { Err(S3Error::new(format!("Unexpected HTTP status code {}", status))) }
+            _ => { Err(S3Error::from_response(&result, metadata)) }
         }
     }
     /// Lists the parts that have been uploaded for a specific multipart upload.
     pub fn list_parts(&self, input: &ListPartsRequest) -> Result<ListPartsOutput, S3Error> {
+        self.list_parts_with_metadata(input).map(|(output, _)| output)
+    }
+
+    /// Like `list_parts`, but also returns the metadata of the response.
+    pub fn list_parts_with_metadata(&self, input: &ListPartsRequest) -> Result<(ListPartsOutput, ResponseMetadata), S3Error> {
         let mut path = String::from("/");
-        if !is_dns_compatible(&input.bucket) {
+        if !self.use_virtual_hosting(&input.bucket) {
             path = format!("{}{}/", path, &input.bucket);
         }
         path = format!("{}{}", path, &input.key);
-        let mut request = SignedRequest::new("GET", "s3", self.region, &path);
+        let mut request = SignedRequest::new("GET", "s3", self.region.clone(), &path);
+        request.set_endpoint(self.endpoint.clone());
 
         let mut params = Params::new();
         params.put("uploadId", &input.upload_id.to_string());
         request.set_params(params);
 
-        if is_dns_compatible(&input.bucket) {
+        if self.use_virtual_hosting(&input.bucket) {
             let hostname = self.hostname(Some(&input.bucket));
             request.set_hostname(Some(hostname));
         }
 
-        let mut result = try!(sign_and_execute(&self.dispatcher, &mut request, try!(self.credentials_provider.credentials())));
+        let mut result = try!(sign_and_execute(&self.dispatcher, &mut request, try!(self.credentials_provider.credentials()), &self.retry_policy, &self.interceptors));
+        let metadata = ResponseMetadata::from_response(&result);
         let status = result.status;
 
         match status {
             200 => {
-                let mut reader = EventReader::from_str(&result.body);
+                let mut reader = EventReader::new(&result.body[..]);
                 let mut stack = XmlResponse::new(reader.events().peekable());
                 stack.next(); // xml start tag
 
-                Ok(try!(ListPartsOutputParser::parse_xml("ListPartsResult", &mut stack)))
+                Ok((try!(ListPartsOutputParser::parse_xml("ListPartsResult", &mut stack)), metadata))
             }
             _ => {
-                println!("Error response body: {}", result.body);
-                Err(S3Error::new(format!("Unexpected HTTP status code {}", status)))
+                println!("Error response body: {}", String::from_utf8_lossy(&result.body));
+                Err(S3Error::from_response(&result, metadata))
             }
         }
     }
     /// Returns the access control list (ACL) of an object.
     pub fn get_object_acl(&self, input: &GetObjectAclRequest) -> Result<GetObjectAclOutput, S3Error> {
-        let mut request = SignedRequest::new("GET", "s3", self.region, "/{Bucket}/{Key+}?acl");
+        self.get_object_acl_with_metadata(input).map(|(output, _)| output)
+    }
+
+    /// Like `get_object_acl`, but also returns the metadata of the response.
+    pub fn get_object_acl_with_metadata(&self, input: &GetObjectAclRequest) -> Result<(GetObjectAclOutput, ResponseMetadata), S3Error> {
+        let mut request = SignedRequest::new("GET", "s3", self.region.clone(), "/{Bucket}/{Key+}?acl");
+        request.set_endpoint(self.endpoint.clone());
         let mut params = Params::new();
         params.put("Action", "GetObjectAcl");
         GetObjectAclRequestWriter::write_params(&mut params, "", input);
         request.set_params(params);
-        let result = try!(sign_and_execute(&self.dispatcher, &mut request, try!(self.credentials_provider.credentials())));
+        let result = try!(sign_and_execute(&self.dispatcher, &mut request, try!(self.credentials_provider.credentials()), &self.retry_policy, &self.interceptors));
+        let metadata = ResponseMetadata::from_response(&result);
         let status = result.status;
-        let mut reader = EventReader::from_str(&result.body);
+        let mut reader = EventReader::new(&result.body[..]);
         let mut stack = XmlResponse::new(reader.events().peekable());
         stack.next(); // xml start tag
         stack.next();
         match status {
             200 => {
-                Ok(try!(GetObjectAclOutputParser::parse_xml("GetObjectAclOutput", &mut stack)))
+                Ok((try!(GetObjectAclOutputParser::parse_xml("GetObjectAclOutput", &mut stack)), metadata))
             }
-            _ => { Err(S3Error::new(format!("Unexpected HTTP status code {}", status))) }
+            _ => { Err(S3Error::from_response(&result, metadata)) }
         }
     }
     /// Uploads a part by copying data from an existing object as data source.
@@ -10562,8 +11136,9 @@ impl<P, D> S3Client<P, D> where P: ProvideAwsCredentials, D: DispatchSignedReque
     //  let ref part_number = input.part_number;
     //  let ref upload_id = input.upload_id;
     //  let ref object_id = input.key;
-    //  let mut request = SignedRequest::new("PUT", "s3", self.region, &format!("/{}?partNumber={}&uploadId={}",
+    //  let mut request = SignedRequest::new("PUT", "s3", self.region.clone(), &format!("/{}?partNumber={}&uploadId={}",
     //      object_id, part_number, upload_id));
+    //  request.set_endpoint(self.endpoint.clone());
     //
     //  let result = request.sign_and_execute(&self.credentials_provider.credentials());
     //  let status = result.status;
@@ -10579,15 +11154,21 @@ impl<P, D> S3Client<P, D> where P: ProvideAwsCredentials, D: DispatchSignedReque
     /// marker, which becomes the latest version of the object. If there isn't a null
     /// version, Amazon S3 does not remove any objects.
     pub fn delete_object(&self, input: &DeleteObjectRequest) -> Result<DeleteObjectOutput, S3Error> {
+        self.delete_object_with_metadata(input).map(|(output, _)| output)
+    }
+
+    /// Like `delete_object`, but also returns the metadata of the response.
+    pub fn delete_object_with_metadata(&self, input: &DeleteObjectRequest) -> Result<(DeleteObjectOutput, ResponseMetadata), S3Error> {
         let mut path = String::from("/");
-        if !is_dns_compatible(&input.bucket) {
+        if !self.use_virtual_hosting(&input.bucket) {
             path = format!("{}{}/", path, &input.bucket);
         }
         path = format!("{}{}/", path, &input.key);
-        let mut request = SignedRequest::new("DELETE", "s3", self.region, &path);
+        let mut request = SignedRequest::new("DELETE", "s3", self.region.clone(), &path);
+        request.set_endpoint(self.endpoint.clone());
         let mut params = Params::new();
 
-        if is_dns_compatible(&input.bucket) {
+        if self.use_virtual_hosting(&input.bucket) {
             let hostname = self.hostname(Some(&input.bucket));
             request.set_hostname(Some(hostname));
         }
@@ -10595,66 +11176,82 @@ impl<P, D> S3Client<P, D> where P: ProvideAwsCredentials, D: DispatchSignedReque
         params.put("Action", "DeleteObject");
         DeleteObjectRequestWriter::write_params(&mut params, "", input);
         request.set_params(params);
-        let result = try!(sign_and_execute(&self.dispatcher, &mut request, try!(self.credentials_provider.credentials())));
+        let result = try!(sign_and_execute(&self.dispatcher, &mut request, try!(self.credentials_provider.credentials()), &self.retry_policy, &self.interceptors));
+        let metadata = ResponseMetadata::from_response(&result);
         let status = result.status;
 
         match status {
             204 => {
-                Ok(DeleteObjectOutput::default())
+                Ok((DeleteObjectOutput::default(), metadata))
                 // Ok(try!(DeleteObjectOutputParser::parse_xml("DeleteObjectOutput", &mut stack)))
             }
-            _ => { Err(S3Error::new(format!("Unexpected HTTP status code {}", status))) }
+            _ => { Err(S3Error::from_response(&result, metadata)) }
         }
     }
     /// Restores an archived copy of an object back into Amazon S3
     pub fn restore_object(&self, input: &RestoreObjectRequest) -> Result<RestoreObjectOutput, S3Error> {
-        let mut request = SignedRequest::new("POST", "s3", self.region, "/{Bucket}/{Key+}?restore");
+        self.restore_object_with_metadata(input).map(|(output, _)| output)
+    }
+
+    /// Like `restore_object`, but also returns the metadata of the response.
+    pub fn restore_object_with_metadata(&self, input: &RestoreObjectRequest) -> Result<(RestoreObjectOutput, ResponseMetadata), S3Error> {
+        let mut request = SignedRequest::new("POST", "s3", self.region.clone(), "/{Bucket}/{Key+}?restore");
+        request.set_endpoint(self.endpoint.clone());
         let mut params = Params::new();
         params.put("Action", "RestoreObject");
         RestoreObjectRequestWriter::write_params(&mut params, "", input);
         request.set_params(params);
-        let result = try!(sign_and_execute(&self.dispatcher, &mut request, try!(self.credentials_provider.credentials())));
+        let result = try!(sign_and_execute(&self.dispatcher, &mut request, try!(self.credentials_provider.credentials()), &self.retry_policy, &self.interceptors));
+        let metadata = ResponseMetadata::from_response(&result);
         let status = result.status;
-        let mut reader = EventReader::from_str(&result.body);
+        let mut reader = EventReader::new(&result.body[..]);
         let mut stack = XmlResponse::new(reader.events().peekable());
         stack.next(); // xml start tag
         stack.next();
         match status {
             200 => {
-                Ok(try!(RestoreObjectOutputParser::parse_xml("RestoreObjectOutput", &mut stack)))
+                Ok((try!(RestoreObjectOutputParser::parse_xml("RestoreObjectOutput", &mut stack)), metadata))
             }
-            _ => { Err(S3Error::new(format!("Unexpected HTTP status code {}", status))) }
+            _ => { Err(S3Error::from_response(&result, metadata)) }
         }
     }
     pub fn get_bucket_replication(&self, input: &GetBucketReplicationRequest) -> Result<GetBucketReplicationOutput, S3Error> {
-        let mut request = SignedRequest::new("GET", "s3", self.region, "/{Bucket}?replication");
+        self.get_bucket_replication_with_metadata(input).map(|(output, _)| output)
+    }
+
+    /// Like `get_bucket_replication`, but also returns the metadata of the response.
+    pub fn get_bucket_replication_with_metadata(&self, input: &GetBucketReplicationRequest) -> Result<(GetBucketReplicationOutput, ResponseMetadata), S3Error> {
+        let mut request = SignedRequest::new("GET", "s3", self.region.clone(), "/{Bucket}?replication");
+        request.set_endpoint(self.endpoint.clone());
         let mut params = Params::new();
         params.put("Action", "GetBucketReplication");
         GetBucketReplicationRequestWriter::write_params(&mut params, "", input);
         request.set_params(params);
-        let result = try!(sign_and_execute(&self.dispatcher, &mut request, try!(self.credentials_provider.credentials())));
+        let result = try!(sign_and_execute(&self.dispatcher, &mut request, try!(self.credentials_provider.credentials()), &self.retry_policy, &self.interceptors));
+        let metadata = ResponseMetadata::from_response(&result);
         let status = result.status;
-        let mut reader = EventReader::from_str(&result.body);
+        let mut reader = EventReader::new(&result.body[..]);
         let mut stack = XmlResponse::new(reader.events().peekable());
         stack.next(); // xml start tag
         stack.next();
         match status {
             200 => {
-                Ok(try!(GetBucketReplicationOutputParser::parse_xml("GetBucketReplicationOutput", &mut stack)))
+                Ok((try!(GetBucketReplicationOutputParser::parse_xml("GetBucketReplicationOutput", &mut stack)), metadata))
             }
-            _ => { Err(S3Error::new(format!("Unexpected HTTP status code {}", status))) }
+            _ => { Err(S3Error::from_response(&result, metadata)) }
         }
     }
 
+    /// Whether `bucket` is addressed as a subdomain rather than as part of the path.
+    fn use_virtual_hosting(&self, bucket: &BucketName) -> bool {
+        self.endpoint.is_none() && self.region.endpoint().is_none() && is_dns_compatible(bucket)
+    }
+
     fn hostname(&self, bucket: Option<&BucketName>) -> String {
-        let host = match self.region {
-                    Region::UsEast1 => "s3.amazonaws.com".to_string(),
-                    Region::CnNorth1 => format!("s3.{}.amazonaws.com.cn", self.region),
-                    _ => format!("s3-{}.amazonaws.com", self.region),
-                };
+        let host = build_hostname("s3", &self.region);
 
         match bucket {
-            Some(b) => format!("{}.s3.amazonaws.com", b),
+            Some(b) => format!("{}.{}", b, host),
             None => host,
         }
     }
@@ -10737,6 +11334,14 @@ impl<P> S3Helper<P> where P: ProvideAwsCredentials {
         self.client.get_object(&request)
     }
 
+    /// Get an object from the specified bucket as a reader, without buffering it in memory
+    pub fn get_object_streaming(&self, bucket_name: &str, object_name: &str) ->  Result<GetObjectStreamingOutput, S3Error> {
+        let mut request = GetObjectRequest::default();
+        request.key = object_name.to_string();
+        request.bucket = bucket_name.to_string();
+        self.client.get_object_streaming(&request)
+    }
+
     /// Upload an object to specified bucket
     pub fn put_object(&self, bucket_name: &str, object_name: &str, object_as_bytes: &[u8]) ->  Result<PutObjectOutput, S3Error> {
         self.put_object_with_optional_reduced_redundancy(bucket_name, object_name, object_as_bytes, false)
@@ -11021,7 +11626,7 @@ pub fn canned_acl_in_aws_format(canned_acl: &CannedAcl) -> String {
 /// `extract_s3_redirect_location` takes a Hyper `Response` and attempts to pull out the temporary endpoint.
 fn extract_s3_redirect_location(response: HttpResponse) -> Result<String, S3Error> {
 
-    let mut reader = EventReader::from_str(&response.body);
+    let mut reader = EventReader::new(&response.body[..]);
     let mut stack = XmlResponse::new(reader.events().peekable());
     stack.next(); // xml start tag
 
@@ -11061,20 +11666,38 @@ fn extract_s3_temporary_endpoint_from_xml<T: Peek + Next>(stack: &mut T) -> Resu
     Err(S3Error::new("Couldn't find redirect location for S3 bucket"))
 }
 
-fn sign_and_execute<D>(dispatcher: &D, request: &mut SignedRequest, creds: AwsCredentials) -> Result<HttpResponse, S3Error> where D: DispatchSignedRequest{
-    request.sign(&creds);
-    let response = try!(dispatcher.dispatch(request));
+fn sign_and_execute<D>(dispatcher: &D, request: &mut SignedRequest, creds: AwsCredentials, retry_policy: &RetryPolicy, interceptors: &InterceptorChain) -> Result<HttpResponse, S3Error> where D: DispatchSignedRequest{
+    let response = try!(retry_policy.sign_and_dispatch(dispatcher, interceptors, request, &creds));
     debug!("Sent request to AWS");
 
     if response.status == 307 {
+        if request.payload_stream().is_some() {
+            return Err(S3Error::new("Got a redirect response, but a streamed payload can't be resent"));
+        }
+
         debug!("Got a redirect response, resending request.");
         // extract location from response, modify request and re-sign and resend.
         let new_hostname = extract_s3_redirect_location(response).unwrap();
         request.set_hostname(Some(new_hostname.to_string()));
 
         // This does a lot of appending and not clearing/creation, so we'll have to do that ourselves:
-        request.sign(&creds);
-        return Ok(try!(dispatcher.dispatch(request)));
+        return Ok(try!(retry_policy.resend(dispatcher, interceptors, request, &creds)));
+    }
+
+    Ok(response)
+}
+
+/// Like `sign_and_execute`, but leaves the body of the final response unread.
+fn sign_and_execute_streaming<D>(dispatcher: &D, request: &mut SignedRequest, creds: AwsCredentials, retry_policy: &RetryPolicy, interceptors: &InterceptorChain) -> Result<HttpStreamingResponse, S3Error> where D: DispatchSignedRequest{
+    let response = try!(retry_policy.sign_and_dispatch_streaming(dispatcher, interceptors, request, &creds));
+    debug!("Sent request to AWS");
+
+    if response.status == 307 {
+        debug!("Got a redirect response, resending request.");
+        let new_hostname = extract_s3_redirect_location(try!(response.buffer())).unwrap();
+        request.set_hostname(Some(new_hostname.to_string()));
+
+        return Ok(try!(retry_policy.resend_streaming(dispatcher, interceptors, request, &creds)));
     }
 
     Ok(response)
@@ -11098,10 +11721,14 @@ mod tests {
     use std::io::{Read, BufReader};
     use std::fs::File;
     use std::str;
+    use std::time::Duration;
 
     use xml::reader::*;
 
+    use error::AwsError;
+    use mock::{MockCredentialsProvider, MockRequestDispatcher};
     use region::Region;
+    use signature::{PayloadSigning, SignedRequest};
     use super::*;
     use super::CompleteMultipartUploadOutputParser;
     use super::CreateMultipartUploadOutputParser;
@@ -11370,4 +11997,235 @@ mod tests {
     fn bucket_with_dot_is_dns_incompatible() {
         assert!(!super::is_dns_compatible(&"foo.bar".to_string()));
     }
+
+    #[test]
+    fn get_object_streaming_reads_body() {
+        let dispatcher = MockRequestDispatcher::with_status(200)
+            .with_body("hello")
+            .with_header("Content-Length".to_string(), "5".to_string())
+            .with_header("ETag".to_string(), "\"abc\"".to_string());
+        let client = S3Client::with_request_dispatcher(dispatcher, MockCredentialsProvider, Region::UsEast1);
+
+        let mut request = GetObjectRequest::default();
+        request.bucket = "rusoto-test".to_string();
+        request.key = "hello.txt".to_string();
+
+        let mut result = client.get_object_streaming(&request).unwrap();
+        let mut body = String::new();
+        result.read_to_string(&mut body).unwrap();
+
+        assert_eq!(body, "hello");
+        assert_eq!(result.output.content_length, 5);
+        assert_eq!(result.output.e_tag, "\"abc\"");
+        assert!(result.output.body.is_empty());
+    }
+
+    #[test]
+    fn get_object_streaming_reads_lengths_over_2_gib() {
+        let dispatcher = MockRequestDispatcher::with_status(200)
+            .with_body("hello")
+            .with_header("Content-Length".to_string(), "21474836480".to_string());
+        let client = S3Client::with_request_dispatcher(dispatcher, MockCredentialsProvider, Region::UsEast1);
+
+        let mut request = GetObjectRequest::default();
+        request.bucket = "rusoto-test".to_string();
+        request.key = "large.bin".to_string();
+
+        let result = client.get_object_streaming(&request).unwrap();
+        assert_eq!(result.output.content_length, 21474836480);
+    }
+
+    #[test]
+    fn get_object_streaming_accepts_partial_content() {
+        let dispatcher = MockRequestDispatcher::with_status(206)
+            .with_body("ell")
+            .with_header("Content-Length".to_string(), "3".to_string())
+            .with_header("Content-Range".to_string(), "bytes 1-3/5".to_string())
+            .with_request_checker(|request: &SignedRequest| {
+                assert_eq!(request.headers["range"], vec![b"bytes=1-3".to_vec()]);
+            });
+        let client = S3Client::with_request_dispatcher(dispatcher, MockCredentialsProvider, Region::UsEast1);
+
+        let mut request = GetObjectRequest::default();
+        request.bucket = "rusoto-test".to_string();
+        request.key = "hello.txt".to_string();
+        request.range = Some("bytes=1-3".to_string());
+
+        let mut result = client.get_object_streaming(&request).unwrap();
+        let mut body = String::new();
+        result.read_to_string(&mut body).unwrap();
+
+        assert_eq!(body, "ell");
+        assert_eq!(result.output.content_range, "bytes 1-3/5");
+    }
+
+    #[test]
+    fn put_object_streaming_uses_chunked_encoding() {
+        let dispatcher = MockRequestDispatcher::with_status(200)
+            .with_request_checker(|request: &SignedRequest| {
+                assert!(request.payload.is_none());
+                assert_eq!(request.payload_stream().unwrap().length(), 11);
+                assert_eq!(request.headers["content-encoding"], vec![b"aws-chunked".to_vec()]);
+                assert_eq!(request.headers["x-amz-decoded-content-length"], vec![b"11".to_vec()]);
+            });
+        let client = S3Client::with_request_dispatcher(dispatcher, MockCredentialsProvider, Region::UsEast1);
+
+        let mut request = PutObjectRequest::default();
+        request.bucket = "rusoto-test".to_string();
+        request.key = "hello.txt".to_string();
+
+        client.put_object_streaming(&request, "hello world".as_bytes(), 11).unwrap();
+    }
+
+    #[test]
+    fn put_object_can_leave_the_payload_unsigned() {
+        let dispatcher = MockRequestDispatcher::with_status(200)
+            .with_request_checker(|request: &SignedRequest| {
+                assert_eq!(request.payload, Some(&b"hello world"[..]));
+                assert_eq!(request.headers["x-amz-content-sha256"], vec![b"UNSIGNED-PAYLOAD".to_vec()]);
+            });
+        let client = S3Client::with_request_dispatcher(dispatcher, MockCredentialsProvider, Region::UsEast1);
+
+        let mut request = PutObjectRequest::default();
+        request.bucket = "rusoto-test".to_string();
+        request.key = "hello.txt".to_string();
+        request.body = Some(b"hello world");
+        request.payload_signing = PayloadSigning::Unsigned;
+
+        client.put_object(&request).unwrap();
+    }
+
+    #[test]
+    fn upload_part_can_sign_a_precomputed_digest() {
+        let digest = "b94d27b9934d3e08a52e52d7da7dabfac484efe37a5380ee9088f7ace2efcde9";
+        let dispatcher = MockRequestDispatcher::with_status(200)
+            .with_header("ETag".to_string(), "\"abc\"".to_string())
+            .with_request_checker(move |request: &SignedRequest| {
+                assert_eq!(request.headers["x-amz-content-sha256"], vec![digest.as_bytes().to_vec()]);
+            });
+        let client = S3Client::with_request_dispatcher(dispatcher, MockCredentialsProvider, Region::UsEast1);
+
+        let mut request = UploadPartRequest::default();
+        request.bucket = "rusoto-test".to_string();
+        request.key = "hello.txt".to_string();
+        request.upload_id = "upload".to_string();
+        request.part_number = 1;
+        request.body = Some(b"hello world");
+        request.payload_signing = PayloadSigning::Precomputed(digest.to_owned());
+
+        assert_eq!(client.upload_part(&request).unwrap(), "\"abc\"");
+    }
+
+    #[test]
+    fn malformed_precomputed_digests_are_rejected() {
+        let client = S3Client::with_request_dispatcher(MockRequestDispatcher::with_status(200), MockCredentialsProvider, Region::UsEast1);
+
+        let mut request = PutObjectRequest::default();
+        request.bucket = "rusoto-test".to_string();
+        request.key = "hello.txt".to_string();
+        request.body = Some(b"hello world");
+        request.payload_signing = PayloadSigning::Precomputed("B94D27B9934D3E08A52E52D7DA7DABFAC484EFE37A5380EE9088F7ACE2EFCDE9".to_owned());
+
+        let err = client.put_object(&request).unwrap_err();
+        assert!(err.message.contains("64 lowercase hex characters"));
+    }
+
+    #[test]
+    fn custom_endpoint_uses_path_style() {
+        let dispatcher = MockRequestDispatcher::with_status(200)
+            .with_request_checker(|request: &SignedRequest| {
+                assert_eq!(request.scheme(), "http");
+                assert_eq!(request.hostname(), "localhost:9000");
+                assert_eq!(request.path(), "/rusoto-test/hello.txt");
+            });
+        let mut client = S3Client::with_request_dispatcher(dispatcher, MockCredentialsProvider, Region::UsEast1);
+        client.set_endpoint("http://localhost:9000".parse().unwrap());
+
+        let mut request = PutObjectRequest::default();
+        request.bucket = "rusoto-test".to_string();
+        request.key = "hello.txt".to_string();
+        request.body = Some(b"hello world");
+
+        client.put_object(&request).unwrap();
+    }
+
+    #[test]
+    fn responses_carry_request_ids() {
+        let dispatcher = MockRequestDispatcher::with_status(204)
+            .with_header("x-amz-request-id".to_string(), "4442587FB7D0A2F9".to_string())
+            .with_header("x-amz-id-2".to_string(), "ZbO8Ip9S2OG6yGXx1vNBXvWXO0Dk4kGiN9+pQm0YtfIg=".to_string());
+        let client = S3Client::with_request_dispatcher(dispatcher, MockCredentialsProvider, Region::UsEast1);
+
+        let mut request = DeleteObjectRequest::default();
+        request.bucket = "rusoto-test".to_string();
+        request.key = "hello.txt".to_string();
+
+        let (_, metadata) = client.delete_object_with_metadata(&request).unwrap();
+        assert_eq!(metadata.status, 204);
+        assert_eq!(metadata.request_id, Some("4442587FB7D0A2F9".to_owned()));
+        assert_eq!(metadata.extended_request_id, Some("ZbO8Ip9S2OG6yGXx1vNBXvWXO0Dk4kGiN9+pQm0YtfIg=".to_owned()));
+
+        let dispatcher = MockRequestDispatcher::with_status(403)
+            .with_body("<Error><Code>AccessDenied</Code><Message>Access Denied</Message><RequestId>656c76696e67</RequestId><HostId>uV2EVE0+2Q6r8U4yZdOd</HostId></Error>");
+        let client = S3Client::with_request_dispatcher(dispatcher, MockCredentialsProvider, Region::UsEast1);
+
+        let err = client.delete_object(&request).unwrap_err();
+        let metadata = err.metadata.unwrap();
+        assert_eq!(metadata.status, 403);
+        assert_eq!(metadata.request_id, Some("656c76696e67".to_owned()));
+        assert_eq!(metadata.extended_request_id, Some("uV2EVE0+2Q6r8U4yZdOd".to_owned()));
+    }
+
+    #[test]
+    fn errors_are_classified() {
+        let mut request = DeleteObjectRequest::default();
+        request.bucket = "rusoto-test".to_string();
+        request.key = "hello.txt".to_string();
+
+        let dispatcher = MockRequestDispatcher::with_status(503)
+            .with_body("<Error><Code>SlowDown</Code><Message>Please reduce your request rate.</Message></Error>");
+        let client = S3Client::with_request_dispatcher(dispatcher, MockCredentialsProvider, Region::UsEast1);
+
+        let err = client.delete_object(&request).unwrap_err();
+        assert_eq!(err.code(), Some("SlowDown"));
+        assert_eq!(err.status(), Some(503));
+        assert!(err.is_throttling() && !err.is_server_error() && err.is_retryable());
+
+        let dispatcher = MockRequestDispatcher::with_status(403)
+            .with_body("<Error><Code>AccessDenied</Code><Message>Access Denied</Message></Error>");
+        let client = S3Client::with_request_dispatcher(dispatcher, MockCredentialsProvider, Region::UsEast1);
+
+        let err = client.delete_object(&request).unwrap_err();
+        assert_eq!(err.code(), Some("AccessDenied"));
+        assert!(err.is_client_error() && !err.is_retryable());
+    }
+
+    #[test]
+    fn presigned_urls_are_not_sent() {
+        let dispatcher = MockRequestDispatcher::with_status(200).with_request_checker(|_: &SignedRequest| {
+            panic!("presigning should not send a request");
+        });
+        let client = S3Client::with_request_dispatcher(dispatcher, MockCredentialsProvider, Region::UsWest2);
+
+        let mut request = GetObjectRequest::default();
+        request.bucket = "rusoto-test".to_string();
+        request.key = "hello.txt".to_string();
+
+        let url = client.presigned_get_object_url(&request, Duration::from_secs(3600)).unwrap();
+        assert!(url.starts_with("https://"));
+        assert!(url.contains("X-Amz-Credential=mock_key%2F"));
+        assert!(url.contains("%2Fus-west-2%2Fs3%2Faws4_request"));
+        assert!(url.contains("&X-Amz-Expires=3600&"));
+        assert!(url.contains("&X-Amz-Signature="));
+
+        let mut request = UploadPartRequest::default();
+        request.bucket = "rusoto-test".to_string();
+        request.key = "hello.txt".to_string();
+        request.upload_id = "upload-id".to_string();
+        request.part_number = 2;
+
+        let url = client.presigned_upload_part_url(&request, Duration::from_secs(60)).unwrap();
+        assert!(url.contains("partNumber=2&"));
+        assert!(url.contains("&uploadId=upload-id"));
+    }
 }
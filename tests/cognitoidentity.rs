@@ -26,7 +26,7 @@ fn should_handle_validation_errors_gracefully() {
     request.identity_pool_id = "invalid".to_string();
 
     match client.list_identities(&request) {
-    	Err(ListIdentitiesError::Validation(msg)) => {
+    	Err(ListIdentitiesError::Validation(msg, _)) => {
     		assert!(msg.contains("identityPoolId"))
     	},
     	err @ _ => panic!("Expected Validation error - got {:#?}", err),
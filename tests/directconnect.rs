@@ -24,7 +24,7 @@ fn should_fail_gracefully() {
     };
 
     match client.describe_connections(&request) {
-        Err(DescribeConnectionsError::DirectConnectClient(msg)) => assert!(msg.contains("Connection ID")),
+        Err(DescribeConnectionsError::DirectConnectClient(msg, _)) => assert!(msg.contains("Connection ID")),
         err @ _ => panic!("Expected DirectConnectClient error, got {:#?}", err)
     };
 }
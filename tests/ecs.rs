@@ -27,7 +27,7 @@ fn main() {
         &ListClustersRequest {
             next_token: Some("bogus".to_owned()), ..Default::default()
         }) {
-        Err(ListClustersError::InvalidParameter(msg)) => assert!(msg.contains("Invalid token bogus")),
+        Err(ListClustersError::InvalidParameter(msg, _)) => assert!(msg.contains("Invalid token bogus")),
         _ => panic!("this should have been an InvalidParameterException ECSError")
     }
 }
@@ -21,7 +21,11 @@ fn should_handle_deprecation_gracefully() {
     let request = DescribeJobFlowsInput::default();
 
     match client.describe_job_flows(&request) {
-        Err(DescribeJobFlowsError::Validation(msg)) => assert!(msg.contains("DescribeJobFlows API is deprecated.")),
+        Err(DescribeJobFlowsError::Validation(msg, metadata)) => {
+            assert!(msg.contains("DescribeJobFlows API is deprecated."));
+            assert_eq!(metadata.status, 400);
+            assert!(metadata.request_id.is_some());
+        },
         err @ _ => panic!("Expected OK response, got {:#?}", err)
     };
 }
@@ -39,7 +39,7 @@ impl<P> TestEtsClient<P>
         TestEtsClient {
             credentials_provider: credentials_provider.clone(),
             region: region,
-            client: EtsClient::new(credentials_provider, region),
+            client: EtsClient::new(credentials_provider, region.clone()),
             s3_helper: None,
             input_bucket: None,
             output_bucket: None,
@@ -49,7 +49,7 @@ impl<P> TestEtsClient<P>
     fn create_s3_helper(&mut self) {
         self.s3_helper = Some(S3Helper::new(
             self.credentials_provider.clone(),
-            self.region
+            self.region.clone()
         ));
     }
 
@@ -93,13 +93,13 @@ impl<P> Drop for TestEtsClient<P>
     fn drop(&mut self) {
         self.s3_helper.take().map(|s3_helper| {
             self.input_bucket.take().map(|bucket| {
-                match s3_helper.delete_bucket(&bucket, self.region) {
+                match s3_helper.delete_bucket(&bucket, self.region.clone()) {
                     Ok(_) => { info!("Deleted S3 bucket: {}", bucket) },
                     Err(e) => { error!("Failed to delete S3 bucket: {}", e) }
                 };
             });
             self.output_bucket.take().map(|bucket| {
-                match s3_helper.delete_bucket(&bucket, self.region) {
+                match s3_helper.delete_bucket(&bucket, self.region.clone()) {
                     Ok(_) => { info!("Deleted S3 bucket: {}", bucket) },
                     Err(e) => { error!("Failed to delete S3 bucket: {}", e) }
                 };